@@ -0,0 +1,110 @@
+// Copyright (c) 2018 FaultyRAM
+//
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or http://www.apache.org/licenses/LICENSE-2.0> or the
+// MIT license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at
+// your option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Memoized searches for Windows SDK installations.
+
+#[cfg(feature = "default-cached")]
+use once_cell::sync::OnceCell;
+use std::collections::BTreeMap;
+use {FindSdkError, SdkInfo, SdkSearch, SdkVersion};
+
+#[derive(Clone, Debug, Default)]
+/// A cache of Windows SDK search results.
+///
+/// Each version is only searched for the first time it is requested; later requests return the
+/// same result without touching the registry or environment. Errors are not cached. Use `refresh`
+/// to discard the cached results, e.g. after installing or uninstalling an SDK.
+pub struct SdkCache {
+    search: SdkSearch,
+    results: BTreeMap<SdkVersion, Option<SdkInfo>>,
+}
+
+impl SdkCache {
+    #[must_use]
+    /// Creates an empty cache that searches using the default options.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[must_use]
+    /// Creates an empty cache that searches using the given options.
+    ///
+    /// The version set in `search` is ignored; the version to search for is passed to `find`
+    /// instead.
+    pub fn with_search(search: SdkSearch) -> Self {
+        Self {
+            search,
+            results: BTreeMap::new(),
+        }
+    }
+
+    /// Returns installation information for a Windows SDK installation, searching for it only if
+    /// this version has not been searched for before.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the search fails, as for `SdkSearch::find`. Errors are not cached, so
+    /// the search is repeated on the next call.
+    pub fn find(&mut self, version: SdkVersion) -> Result<Option<SdkInfo>, FindSdkError> {
+        if let Some(res) = self.results.get(&version) {
+            return Ok(res.clone());
+        }
+        let mut search = self.search.clone();
+        let res = search.version(version).find()?;
+        Ok(self.results.entry(version).or_insert(res).clone())
+    }
+
+    /// Discards all cached results.
+    pub fn refresh(&mut self) {
+        self.results.clear();
+    }
+}
+
+#[cfg(feature = "default-cached")]
+/// Returns the process-wide result of `SdkInfo::find(SdkVersion::Any)`, searching only on the
+/// first successful call.
+pub fn default_cached() -> Result<&'static Option<SdkInfo>, FindSdkError> {
+    static DEFAULT: OnceCell<Option<SdkInfo>> = OnceCell::new();
+    DEFAULT.get_or_try_init(|| SdkInfo::find(SdkVersion::Any))
+}
+
+#[cfg(test)]
+mod tests {
+    use {SdkCache, SdkInfo, SdkVersion};
+
+    #[test]
+    fn cached() {
+        let mut cache = SdkCache::new();
+        let first = cache
+            .find(SdkVersion::Any)
+            .expect("could not retrieve Windows SDK info from registry");
+        let second = cache.find(SdkVersion::Any).expect("cached lookup failed");
+        assert_eq!(first, second);
+        assert_eq!(
+            first,
+            SdkInfo::find(SdkVersion::Any)
+                .expect("could not retrieve Windows SDK info from registry")
+        );
+        cache.refresh();
+        assert!(cache.results.is_empty());
+    }
+
+    #[test]
+    #[cfg(feature = "default-cached")]
+    fn default_cached() {
+        let first =
+            SdkInfo::default_cached().expect("could not retrieve Windows SDK info from registry");
+        let second = SdkInfo::default_cached().expect("cached lookup failed");
+        assert!(::std::ptr::eq(first, second));
+        assert_eq!(
+            *first,
+            SdkInfo::find(SdkVersion::Any)
+                .expect("could not retrieve Windows SDK info from registry")
+        );
+    }
+}
@@ -0,0 +1,65 @@
+// Copyright (c) 2018 FaultyRAM
+//
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or http://www.apache.org/licenses/LICENSE-2.0> or the
+// MIT license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at
+// your option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Errors that can occur while searching for Windows SDK installations.
+
+use std::error::Error;
+use std::fmt::{self, Display, Formatter};
+use std::io;
+
+#[derive(Debug)]
+/// An error that occurred while searching for a Windows SDK installation.
+///
+/// A Windows SDK that simply is not installed is not an error; searches return `Ok(None)` in that
+/// case.
+pub enum FindSdkError {
+    /// The registry could not be read.
+    Registry(io::Error),
+    /// A registry key for a Windows SDK exists, but its values could not be read.
+    ///
+    /// This usually means that the SDK was not uninstalled cleanly.
+    MalformedValue {
+        /// The path to the registry key.
+        key: String,
+    },
+    /// An environment variable describing a Windows SDK has an invalid value.
+    InvalidEnvVar {
+        /// The name of the environment variable.
+        name: String,
+    },
+}
+
+impl Display for FindSdkError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match *self {
+            FindSdkError::Registry(ref e) => write!(f, "could not read the registry: {e}"),
+            FindSdkError::MalformedValue { ref key } => write!(
+                f,
+                "registry key `{key}` does not describe a valid Windows SDK installation"
+            ),
+            FindSdkError::InvalidEnvVar { ref name } => {
+                write!(f, "environment variable `{name}` has an invalid value")
+            }
+        }
+    }
+}
+
+impl Error for FindSdkError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match *self {
+            FindSdkError::Registry(ref e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for FindSdkError {
+    fn from(e: io::Error) -> Self {
+        FindSdkError::Registry(e)
+    }
+}
@@ -0,0 +1,92 @@
+// Copyright (c) 2018 FaultyRAM
+//
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or http://www.apache.org/licenses/LICENSE-2.0> or the
+// MIT license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at
+// your option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Searches for Windows SDK installations that run on the `tokio` blocking thread pool.
+
+use std::future::Future;
+use std::io;
+use std::panic;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use tokio::task::{self, JoinHandle};
+use {FindSdkError, SdkInfo, SdkSearch, SdkVersion};
+
+#[derive(Debug)]
+/// A search for a Windows SDK installation running on the `tokio` blocking thread pool.
+///
+/// This is returned by `SdkSearch::find_async` and `SdkInfo::find_async`, and resolves to the same
+/// result as the equivalent call to `find`.
+pub struct FindFuture {
+    handle: JoinHandle<Result<Option<SdkInfo>, FindSdkError>>,
+}
+
+impl Future for FindFuture {
+    type Output = Result<Option<SdkInfo>, FindSdkError>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Self::Output> {
+        match Pin::new(&mut self.handle).poll(cx) {
+            Poll::Pending => Poll::Pending,
+            Poll::Ready(Ok(res)) => Poll::Ready(res),
+            Poll::Ready(Err(e)) => match e.try_into_panic() {
+                Ok(payload) => panic::resume_unwind(payload),
+                // The task can only be cancelled if the runtime is shutting down.
+                Err(e) => Poll::Ready(Err(FindSdkError::Registry(io::Error::other(e)))),
+            },
+        }
+    }
+}
+
+impl SdkSearch {
+    #[must_use]
+    /// Like `find`, but runs the search on the `tokio` blocking thread pool.
+    ///
+    /// This must be called from within a `tokio` runtime. The options are cloned when the search
+    /// starts, so changing them afterwards has no effect on it.
+    pub fn find_async(&self) -> FindFuture {
+        let search = self.clone();
+        FindFuture {
+            handle: task::spawn_blocking(move || search.find()),
+        }
+    }
+}
+
+impl SdkInfo {
+    #[must_use]
+    /// Like `find`, but runs the search on the `tokio` blocking thread pool.
+    ///
+    /// This is equivalent to `SdkSearch::new().version(version).find_async()`.
+    pub fn find_async(version: SdkVersion) -> FindFuture {
+        SdkSearch::new().version(version).find_async()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::runtime::Builder;
+    use {SdkInfo, SdkVersion};
+
+    #[test]
+    fn find_async() {
+        let runtime = Builder::new_current_thread()
+            .build()
+            .expect("could not start tokio runtime");
+        // `find_async` spawns its task immediately, so it must be called inside the runtime.
+        let future = {
+            let _guard = runtime.enter();
+            SdkInfo::find_async(SdkVersion::Any)
+        };
+        let info = runtime
+            .block_on(future)
+            .expect("could not retrieve Windows SDK info from registry");
+        assert_eq!(
+            info,
+            SdkInfo::find(SdkVersion::Any)
+                .expect("could not retrieve Windows SDK info from registry")
+        );
+    }
+}
@@ -0,0 +1,76 @@
+// Copyright (c) 2018 FaultyRAM
+//
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or http://www.apache.org/licenses/LICENSE-2.0> or the
+// MIT license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at
+// your option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Conversion of Windows SDK installation information to and from JSON.
+
+use serde_json;
+use SdkInfo;
+
+impl SdkInfo {
+    /// Serializes installation information as JSON, e.g. to cache it between builds.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a path is not valid Unicode.
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string(self)
+    }
+
+    /// Deserializes installation information from JSON produced by `to_json`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `json` is not valid JSON, or does not describe an installation.
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(json)
+    }
+}
+
+#[cfg(all(test, target_os = "windows"))]
+mod tests {
+    use std::collections::BTreeMap;
+    use std::path::PathBuf;
+    use {Flavor, SdkInfo};
+
+    #[test]
+    fn round_trip() {
+        let mut raw_values = BTreeMap::new();
+        let _ = raw_values.insert("ProductVersion".to_owned(), "8.1.25984".to_owned());
+        let info = SdkInfo {
+            installation_folder: PathBuf::from(r"C:\Program Files (x86)\Windows Kits\8.1\"),
+            product_name: Some("Windows Software Development Kit for Windows 8.1".to_owned()),
+            product_version: "8.1.25984".to_owned(),
+            bin_dir_from_env: Some(PathBuf::from(
+                r"C:\Program Files (x86)\Windows Kits\8.1\bin",
+            )),
+            flavor: Some(Flavor::Standard),
+            raw_values,
+            registry_last_write: None,
+        };
+        let json = info.to_json().expect("could not serialize to JSON");
+        assert_eq!(
+            SdkInfo::from_json(&json).expect("could not deserialize from JSON"),
+            info
+        );
+        let bare = SdkInfo {
+            installation_folder: PathBuf::from(r"C:\Program Files (x86)\Windows Kits\10\"),
+            product_name: None,
+            product_version: "10.0.17763".to_owned(),
+            bin_dir_from_env: None,
+            flavor: None,
+            raw_values: BTreeMap::new(),
+            registry_last_write: None,
+        };
+        let json = bare.to_json().expect("could not serialize to JSON");
+        assert_eq!(
+            SdkInfo::from_json(&json).expect("could not deserialize from JSON"),
+            bare
+        );
+        assert!(SdkInfo::from_json("{}").is_err());
+    }
+}
@@ -8,11 +8,12 @@
 
 //! Provides support for detecting Windows SDK installations.
 
-#![cfg(target_os = "windows")]
-#![forbid(warnings)]
+// The public API is only available on Windows. Tests are built everywhere, so that the search
+// logic can be tested against a mock registry on any platform.
+#![cfg(any(target_os = "windows", test))]
+#![deny(warnings)]
 #![forbid(future_incompatible)]
 #![deny(unused)]
-#![forbid(box_pointers)]
 #![forbid(missing_copy_implementations)]
 #![forbid(missing_debug_implementations)]
 #![forbid(missing_docs)]
@@ -23,75 +24,435 @@
 #![deny(unused_qualifications)]
 #![forbid(unused_results)]
 #![forbid(variant_size_differences)]
-#![cfg_attr(feature = "cargo-clippy", forbid(clippy))]
-#![cfg_attr(feature = "cargo-clippy", forbid(clippy_pedantic))]
-#![cfg_attr(feature = "cargo-clippy", forbid(clippy_complexity))]
-#![cfg_attr(feature = "cargo-clippy", deny(clippy_correctness))]
-#![cfg_attr(feature = "cargo-clippy", forbid(clippy_perf))]
-#![cfg_attr(feature = "cargo-clippy", forbid(clippy_style))]
+#![deny(clippy::all)]
+#![forbid(clippy::pedantic)]
+#![forbid(clippy::complexity)]
+#![deny(clippy::correctness)]
+#![forbid(clippy::perf)]
+#![forbid(clippy::style)]
 
+#[cfg(feature = "logging")]
+#[macro_use]
+extern crate log;
+#[cfg(feature = "default-cached")]
+extern crate once_cell;
+extern crate serde;
 #[macro_use]
 extern crate serde_derive;
+#[cfg(feature = "serde_json")]
+extern crate serde_json;
+#[cfg(feature = "tokio")]
+extern crate tokio;
+#[cfg(target_os = "windows")]
 extern crate winreg;
 
+#[cfg(test)]
+extern crate serde_test;
+
+// Without the `logging` feature, these stand in for the `log` crate's macros and do nothing.
+#[cfg(not(feature = "logging"))]
+macro_rules! debug {
+    ($($arg:tt)*) => {
+        if false {
+            let _ = format_args!($($arg)*);
+        }
+    };
+}
+#[cfg(not(feature = "logging"))]
+macro_rules! trace {
+    ($($arg:tt)*) => {
+        if false {
+            let _ = format_args!($($arg)*);
+        }
+    };
+}
+#[cfg(not(feature = "logging"))]
+macro_rules! warn {
+    ($($arg:tt)*) => {
+        if false {
+            let _ = format_args!($($arg)*);
+        }
+    };
+}
+
+mod cache;
+mod error;
+#[cfg(feature = "tokio")]
+mod future;
+#[cfg(feature = "serde_json")]
+mod json;
+mod pe;
+mod probe;
+mod registry;
+mod report;
+mod search;
+mod version;
+#[cfg(feature = "vs-setup")]
+mod vs_setup;
+mod wdk;
+
+pub use cache::SdkCache;
+pub use error::FindSdkError;
+#[cfg(feature = "tokio")]
+pub use future::FindFuture;
+pub use registry::{RegistryHive, RegistryView};
+pub use report::{SearchOutcome, SearchReport, SearchStep};
+pub use search::{Flavor, FlavorPreference, SdkIter, SdkSearch, DEFAULT_COMPONENT_SUBKEYS};
+pub use version::{ParseVersionError, Version};
+pub use wdk::WdkInfo;
+
+use serde::{Deserialize, Deserializer};
+use std::borrow::Cow;
+use std::cmp::{Ordering, Reverse};
+use std::collections::BTreeMap;
 use std::env;
-use std::ffi::OsStr;
-use std::io::{self, ErrorKind};
+use std::error::Error;
+use std::fmt::{self, Display, Formatter};
+use std::fs;
+use std::io;
 use std::path::{Path, PathBuf};
-use winreg::enums::{KEY_WOW64_32KEY, HKEY_LOCAL_MACHINE, KEY_QUERY_VALUE};
-use winreg::RegKey;
-
-const V10_0_REG_KEY: &str = r"SOFTWARE\Microsoft\Microsoft SDKs\Windows\v10.0";
-const V8_1A_REG_KEY: &str = r"SOFTWARE\Microsoft\Microsoft SDKs\Windows\v8.1A";
-const V8_1_REG_KEY: &str = r"SOFTWARE\Microsoft\Microsoft SDKs\Windows\v8.1";
-const V8_0A_REG_KEY: &str = r"SOFTWARE\Microsoft\Microsoft SDKs\Windows\v8.0A";
-const V8_0_REG_KEY: &str = r"SOFTWARE\Microsoft\Microsoft SDKs\Windows\v8.0";
-const V7_1A_REG_KEY: &str = r"SOFTWARE\Microsoft\Microsoft SDKs\Windows\v7.1A";
-const V7_1_REG_KEY: &str = r"SOFTWARE\Microsoft\Microsoft SDKs\Windows\v7.1";
-const V7_0A_REG_KEY: &str = r"SOFTWARE\Microsoft\Microsoft SDKs\Windows\v7.0a";
-const V7_0_REG_KEY: &str = r"SOFTWARE\Microsoft\Microsoft SDKs\Windows\v7.0";
-const V6_1A_REG_KEY: &str = r"SOFTWARE\Microsoft\Microsoft SDKs\Windows\v6.1a";
-const V6_1_REG_KEY: &str = r"SOFTWARE\Microsoft\Microsoft SDKs\Windows\v6.1";
-const V6_0A_REG_KEY: &str = r"SOFTWARE\Microsoft\Microsoft SDKs\Windows\v6.0a";
-const V6_0_REG_KEY: &str = r"SOFTWARE\Microsoft\Microsoft SDKs\Windows\v6.0";
+use std::str::FromStr;
+use std::time::SystemTime;
 
-#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+/// The order in which `SdkVersion::Any` tries each version.
+const ANY_SEARCH_ORDER: [SdkVersion; 8] = [
+    SdkVersion::Env,
+    SdkVersion::V10_0,
+    SdkVersion::V8_1,
+    SdkVersion::V8_0,
+    SdkVersion::V7_1,
+    SdkVersion::V7_0,
+    SdkVersion::V6_1,
+    SdkVersion::V6_0,
+];
+
+/// How many levels of subfolders of `bin` that `SdkInfo::relative_tool_paths` looks in.
+///
+/// Tools are usually kept in `bin\<build>\<arch>`, with a few in further subfolders of that.
+const MAX_TOOL_DEPTH: usize = 4;
+
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
 /// Windows SDK versions.
 ///
 /// Note that prior to v10.0, each Windows SDK came in two flavours; one with an `A` suffix in its
 /// version number, and one without. For convenience, this crate detects both, preferring the `A`
 /// suffix version if present.
+///
+/// Versions are ordered by release, so that e.g. `SdkVersion::V10_0 > SdkVersion::V8_1`. `Any` and
+/// `Env` do not name a particular release, and are ordered below every concrete version (with
+/// `Any < Env`).
+///
+/// Versions are serialized using the same labels as their `Display` implementation, e.g. `v10.0`.
 pub enum SdkVersion {
     /// Any Windows SDK version.
     ///
     /// This is either one specified by environment variables or, if that is not available, the
     /// latest version found in the registry.
+    #[serde(rename = "any")]
     Any,
     /// A Windows SDK installation specified by environment variables.
+    #[serde(rename = "env")]
     Env,
     /// The Windows 10.0 SDK.
+    #[serde(rename = "v10.0")]
     V10_0,
     /// The Windows 8.1 SDK.
+    #[serde(rename = "v8.1")]
     V8_1,
     /// The Windows 8.0 SDK.
+    #[serde(rename = "v8.0")]
     V8_0,
     /// The Windows 7.1 SDK.
+    #[serde(rename = "v7.1")]
     V7_1,
     /// The Windows 7.0 SDK.
+    #[serde(rename = "v7.0")]
     V7_0,
     /// The Windows 6.1 SDK.
+    #[serde(rename = "v6.1")]
     V6_1,
     /// The Windows 6.0 SDK.
+    #[serde(rename = "v6.0")]
     V6_0,
 }
 
-#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+impl SdkVersion {
+    #[must_use]
+    /// Returns every concrete Windows SDK version (i.e. excluding `Any` and `Env`), newest first.
+    ///
+    /// This is the order in which `SdkVersion::Any` searches the registry.
+    pub fn all_concrete() -> &'static [SdkVersion] {
+        &ANY_SEARCH_ORDER[1..]
+    }
+
+    #[must_use]
+    /// Returns the release that a numeric version number belongs to, e.g. `V8_1` for `8.1.0.0`.
+    ///
+    /// Releases are told apart by the major and minor components alone, so every Windows 10 SDK
+    /// build (e.g. `10.0.19041.0`) belongs to `V10_0`. This is useful for installations specified
+    /// by environment variables, which only give a version number. Returns `None` if the version
+    /// number does not belong to a known release, e.g. `8.2` or `11.0`.
+    pub fn from_version(version: &Version) -> Option<Self> {
+        match (version.major, version.minor) {
+            (10, 0) => Some(SdkVersion::V10_0),
+            (8, 1) => Some(SdkVersion::V8_1),
+            (8, 0) => Some(SdkVersion::V8_0),
+            (7, 1) => Some(SdkVersion::V7_1),
+            (7, 0) => Some(SdkVersion::V7_0),
+            (6, 1) => Some(SdkVersion::V6_1),
+            (6, 0) => Some(SdkVersion::V6_0),
+            _ => None,
+        }
+    }
+
+    #[must_use]
+    /// Returns the registry key that describes this release, relative to `HKEY_LOCAL_MACHINE`.
+    ///
+    /// For releases that come in two flavours, this is the standard flavour's key; use
+    /// `flavored_registry_subkey` for the other. Returns `None` for `SdkVersion::Any` and
+    /// `SdkVersion::Env`, which do not correspond to a single key.
+    pub fn registry_subkey(self) -> Option<&'static str> {
+        self.flavored_registry_subkey(Flavor::Standard)
+    }
+
+    #[must_use]
+    /// Returns the registry key that describes the given flavour of this release, relative to
+    /// `HKEY_LOCAL_MACHINE`.
+    ///
+    /// The Windows 10 SDK only comes in one flavour, so its key is returned for either flavour.
+    pub fn flavored_registry_subkey(self, flavor: Flavor) -> Option<&'static str> {
+        search::registry_keys(self).map(|(with_a, standard)| match flavor {
+            Flavor::WithA => with_a,
+            Flavor::Standard => standard,
+        })
+    }
+
+    #[must_use]
+    /// Returns every registry key that is searched for this release, relative to
+    /// `HKEY_LOCAL_MACHINE`.
+    ///
+    /// For releases that come in two flavours, this is the `A` flavour's key followed by the
+    /// standard flavour's key. The Windows 10 SDK has a single key. Returns an empty slice for
+    /// `SdkVersion::Any` and `SdkVersion::Env`.
+    pub fn registry_keys(self) -> &'static [&'static str] {
+        search::all_registry_keys(self)
+    }
+
+    /// Returns the label used to display and parse this version, e.g. `v10.0`.
+    fn name(self) -> &'static str {
+        match self {
+            SdkVersion::Any => "any",
+            SdkVersion::Env => "env",
+            SdkVersion::V10_0 => "v10.0",
+            SdkVersion::V8_1 => "v8.1",
+            SdkVersion::V8_0 => "v8.0",
+            SdkVersion::V7_1 => "v7.1",
+            SdkVersion::V7_0 => "v7.0",
+            SdkVersion::V6_1 => "v6.1",
+            SdkVersion::V6_0 => "v6.0",
+        }
+    }
+
+    fn rank(self) -> u8 {
+        match self {
+            SdkVersion::Any => 0,
+            SdkVersion::Env => 1,
+            SdkVersion::V6_0 => 2,
+            SdkVersion::V6_1 => 3,
+            SdkVersion::V7_0 => 4,
+            SdkVersion::V7_1 => 5,
+            SdkVersion::V8_0 => 6,
+            SdkVersion::V8_1 => 7,
+            SdkVersion::V10_0 => 8,
+        }
+    }
+}
+
+impl Ord for SdkVersion {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.rank().cmp(&other.rank())
+    }
+}
+
+impl PartialOrd for SdkVersion {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Display for SdkVersion {
+    /// Formats a version as a short label, e.g. `v10.0`, `any` or `env`.
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for SdkVersion {
+    type Err = ParseSdkVersionError;
+
+    /// Parses a version from a string such as `any`, `env`, `v10.0` or `10.0`, ignoring case.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        [SdkVersion::Any, SdkVersion::Env]
+            .iter()
+            .chain(Self::all_concrete())
+            .find(|v| {
+                let name = v.name();
+                name.eq_ignore_ascii_case(s)
+                    || (name.starts_with('v') && name[1..].eq_ignore_ascii_case(s))
+            })
+            .copied()
+            .ok_or(ParseSdkVersionError { _priv: () })
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+/// The error returned when parsing an `SdkVersion` from a string fails.
+pub struct ParseSdkVersionError {
+    _priv: (),
+}
+
+impl Display for ParseSdkVersionError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.write_str("unrecognised Windows SDK version")
+    }
+}
+
+impl Error for ParseSdkVersionError {}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+/// Processor architectures supported by Windows SDK tools and libraries.
+pub enum Arch {
+    /// 32-bit x86.
+    X86,
+    /// 64-bit x86 (also known as x86-64 or AMD64).
+    X64,
+    /// 32-bit ARM.
+    Arm,
+    /// 64-bit ARM (also known as `AArch64`).
+    Arm64,
+}
+
+impl Arch {
+    #[must_use]
+    /// Returns every architecture, in the order that they should usually be tried.
+    pub fn all() -> &'static [Arch] {
+        &[Arch::X64, Arch::X86, Arch::Arm64, Arch::Arm]
+    }
+
+    #[must_use]
+    /// Returns the architecture that this crate was compiled for.
+    ///
+    /// When called from a build script, this is the architecture of the machine running the build.
+    /// Returns `None` if the architecture is not one supported by the Windows SDK.
+    pub fn host() -> Option<Self> {
+        match env::consts::ARCH {
+            "x86" => Some(Arch::X86),
+            "x86_64" => Some(Arch::X64),
+            "arm" => Some(Arch::Arm),
+            "aarch64" => Some(Arch::Arm64),
+            _ => None,
+        }
+    }
+
+    /// Returns the name used for this architecture's folders within a Windows SDK.
+    fn dir_name(self) -> &'static str {
+        match self {
+            Arch::X86 => "x86",
+            Arch::X64 => "x64",
+            Arch::Arm => "arm",
+            Arch::Arm64 => "arm64",
+        }
+    }
+}
+
+impl Display for Arch {
+    /// Formats an architecture using the name of its folders within a Windows SDK, e.g. `x64`.
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.write_str(self.dir_name())
+    }
+}
+
+impl FromStr for Arch {
+    type Err = ParseArchError;
+
+    /// Parses an architecture from the name of its folders within a Windows SDK, ignoring case.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        [Arch::X86, Arch::X64, Arch::Arm, Arch::Arm64]
+            .iter()
+            .find(|a| a.dir_name().eq_ignore_ascii_case(s))
+            .copied()
+            .ok_or(ParseArchError { _priv: () })
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+/// The error returned when parsing an `Arch` from a string fails.
+pub struct ParseArchError {
+    _priv: (),
+}
+
+impl Display for ParseArchError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.write_str("unrecognised Windows SDK architecture")
+    }
+}
+
+impl Error for ParseArchError {}
+
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+/// A field that differs between two `SdkInfo` instances, as returned by `SdkInfo::diff`.
+pub enum FieldDiff {
+    /// The installation folders differ.
+    InstallationFolder,
+    /// The product names differ.
+    ProductName,
+    /// The version numbers differ.
+    ProductVersion,
+}
+
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+/// An HLSL shader compiler included with the Windows SDK, as looked for by
+/// `SdkInfo::shader_compiler`.
+pub enum ShaderCompiler {
+    /// The legacy compiler, `fxc.exe`, for shader models up to 5.1.
+    Fxc,
+    /// The DirectX Shader Compiler, `dxc.exe`, for shader model 6.0 and later.
+    ///
+    /// This is only included with Windows 10 SDK builds from 10.0.17763 onwards.
+    Dxc,
+}
+
+impl ShaderCompiler {
+    #[must_use]
+    /// Returns the file name of this compiler's executable.
+    pub fn file_name(self) -> &'static str {
+        match self {
+            ShaderCompiler::Fxc => "fxc.exe",
+            ShaderCompiler::Dxc => "dxc.exe",
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
 #[serde(rename_all = "PascalCase")]
 /// Information about a Windows SDK installation.
+///
+/// When serialized, fields are always written in the same order, starting with
+/// `InstallationFolder`, `ProductName` and `ProductVersion`. Combined with sorting by
+/// `ordering_key`, this gives output that can be diffed and committed to version control.
 pub struct SdkInfo {
+    #[serde(deserialize_with = "deserialize_folder")]
     installation_folder: PathBuf,
     product_name: Option<String>,
     product_version: String,
+    #[serde(default)]
+    bin_dir_from_env: Option<PathBuf>,
+    #[serde(default)]
+    flavor: Option<Flavor>,
+    #[serde(default)]
+    raw_values: BTreeMap<String, String>,
+    #[serde(default)]
+    registry_last_write: Option<SystemTime>,
 }
 
 impl SdkInfo {
@@ -100,127 +461,2589 @@ impl SdkInfo {
     /// If `SdkVersion::Any` is specified, this method will first query environment variables, then
     /// search the registry for the latest Windows SDK recognised by this crate. If a specific
     /// version is specified, this method will only look for that version before giving up.
-    pub fn find(version: SdkVersion) -> io::Result<Option<Self>> {
-        match version {
-            SdkVersion::Any => {
-                use SdkVersion::*;
-                let vers = [Env, V10_0, V8_1, V8_0, V7_1, V7_0, V6_1, V6_0];
-                for res in vers.iter().map(|v| Self::find(*v)) {
-                    match res {
-                        Ok(None) => (),
-                        _ => return res,
-                    }
-                }
-                Ok(None)
+    ///
+    /// If the `FIND_WINSDK_PREFER` environment variable names a version, e.g. `v8.1`,
+    /// `SdkVersion::Any` searches for that version first; see `SdkSearch::use_prefer_var`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the search fails, as for `SdkSearch::find`.
+    pub fn find(version: SdkVersion) -> Result<Option<Self>, FindSdkError> {
+        SdkSearch::new().version(version).find()
+    }
+
+    /// Returns installation information for a Windows SDK installation, along with the version
+    /// that matched.
+    ///
+    /// This is equivalent to `SdkSearch::new().version(version).find_detailed()`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the search fails, as for `SdkSearch::find`.
+    pub fn find_detailed(version: SdkVersion) -> Result<Option<(SdkVersion, Self)>, FindSdkError> {
+        SdkSearch::new().version(version).find_detailed()
+    }
+
+    /// Returns installation information for the newest Windows SDK installation that is at least
+    /// as new as `min`.
+    ///
+    /// This is equivalent to `SdkSearch::new().min_version(min).find()`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the search fails, as for `SdkSearch::find`.
+    pub fn find_at_least(min: SdkVersion) -> Result<Option<Self>, FindSdkError> {
+        SdkSearch::new().min_version(min).find()
+    }
+
+    #[cfg(feature = "default-cached")]
+    /// Returns installation information for the default Windows SDK installation, searching for
+    /// it only the first time this is called in a process.
+    ///
+    /// This is equivalent to `find(SdkVersion::Any)`, but later calls return the same result
+    /// without touching the registry or environment, so changes made after the first call (e.g.
+    /// installing an SDK or changing `WindowsSdkDir`) are not seen. Unlike `SdkCache`, the result
+    /// cannot be refreshed. Errors are not cached, so a failed search is retried on the next call.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the search fails, as for `SdkSearch::find`.
+    pub fn default_cached() -> Result<&'static Option<Self>, FindSdkError> {
+        cache::default_cached()
+    }
+
+    /// Like `find`, but with control over which flavours are searched for, for releases that come
+    /// in two flavours.
+    ///
+    /// This is equivalent to `SdkSearch::new().version(version).flavor_preference(flavor).find()`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the search fails, as for `SdkSearch::find`.
+    pub fn find_with_flavor(
+        version: SdkVersion,
+        flavor: FlavorPreference,
+    ) -> Result<Option<Self>, FindSdkError> {
+        SdkSearch::new()
+            .version(version)
+            .flavor_preference(flavor)
+            .find()
+    }
+
+    /// Returns installation information for every Windows SDK installation that can be found.
+    ///
+    /// Installations are listed in the same order that `SdkVersion::Any` searches them, so the
+    /// first entry (if any) is the one that `find(SdkVersion::Any)` would return. An installation
+    /// that is found more than once, e.g. via both environment variables and the registry, is only
+    /// listed once.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the search fails, as for `SdkSearch::find`.
+    pub fn find_all() -> Result<Vec<Self>, FindSdkError> {
+        SdkSearch::new().find_all()
+    }
+
+    /// Searches for each of the given versions in turn.
+    ///
+    /// This is equivalent to `SdkSearch::new().find_versions(versions)`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the search fails, as for `SdkSearch::find`.
+    pub fn find_versions(
+        versions: &[SdkVersion],
+    ) -> Result<Vec<(SdkVersion, Option<Self>)>, FindSdkError> {
+        SdkSearch::new().find_versions(versions)
+    }
+
+    /// Returns installation information for the first of the given versions that is installed.
+    ///
+    /// This is like `find(SdkVersion::Any)`, but searches versions in the given order instead of
+    /// newest first; `SdkVersion::Env` can be included to choose where environment variables come
+    /// in that order. This is equivalent to `SdkSearch::new().find_in_order(order)`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the search fails, as for `SdkSearch::find`.
+    pub fn find_any_with_fallback_order(
+        order: &[SdkVersion],
+    ) -> Result<Option<Self>, FindSdkError> {
+        SdkSearch::new().find_in_order(order)
+    }
+
+    #[must_use]
+    /// Returns an iterator over every Windows SDK installation that can be found, in the same
+    /// order as `find_all`.
+    ///
+    /// Each version is only searched for as the iterator advances, so this avoids unnecessary
+    /// registry reads when only the first installation meeting some condition is wanted. This is
+    /// equivalent to `SdkSearch::new().iter()`.
+    pub fn iter() -> SdkIter {
+        SdkSearch::new().iter()
+    }
+
+    /// Returns installation information for each Windows 10 SDK build that is installed, newest
+    /// first.
+    ///
+    /// The Windows 10 SDK is registered as a single installation, but several builds can be
+    /// installed side by side in its installation folder. This returns one entry per build found
+    /// by `build_numbers`, each with its own `product_version`, so that `include_dir`, `lib_dir`
+    /// and similar methods use that build's folders. If the `Include` folder cannot be read, only
+    /// the registered installation is returned. Preview builds are left out; use
+    /// `SdkSearch::include_preview` to list them too.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the search fails, as for `SdkSearch::find`.
+    pub fn find_all_v10() -> Result<Vec<Self>, FindSdkError> {
+        SdkSearch::new().find_all_v10()
+    }
+
+    /// Returns installation information for the given Windows 10 SDK build if it is installed,
+    /// or otherwise for any Windows SDK installation, as with `SdkVersion::Any`.
+    ///
+    /// `build` is a build number such as `10.0.17763` or `10.0.17763.0`. The build is installed if
+    /// the registered Windows 10 SDK's `Include` folder has a subfolder for it; if so, the
+    /// returned installation is pinned to that build, as with `find_all_v10`. This allows builds
+    /// to be reproduced with a particular SDK build where it is available.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the search fails, as for `SdkSearch::find`.
+    pub fn find_preferring_build(build: &str) -> Result<Option<Self>, FindSdkError> {
+        if let Some(info) = Self::find(SdkVersion::V10_0)? {
+            let pinned = Self {
+                product_version: search::trim_zero_revision(build),
+                ..info
+            };
+            let folder = pinned.build_folder_name();
+            if pinned
+                .installation_folder
+                .join("Include")
+                .join(folder)
+                .is_dir()
+            {
+                return Ok(Some(pinned));
             }
-            SdkVersion::Env => Ok(Self::query_env()),
-            SdkVersion::V10_0 => Self::query_reg(V10_0_REG_KEY),
-            SdkVersion::V8_1 => Self::find_double_release((V8_1A_REG_KEY, V8_1_REG_KEY)),
-            SdkVersion::V8_0 => Self::find_double_release((V8_0A_REG_KEY, V8_0_REG_KEY)),
-            SdkVersion::V7_1 => Self::find_double_release((V7_1A_REG_KEY, V7_1_REG_KEY)),
-            SdkVersion::V7_0 => Self::find_double_release((V7_0A_REG_KEY, V7_0_REG_KEY)),
-            SdkVersion::V6_1 => Self::find_double_release((V6_1A_REG_KEY, V6_1_REG_KEY)),
-            SdkVersion::V6_0 => Self::find_double_release((V6_0A_REG_KEY, V6_0_REG_KEY)),
         }
+        Self::find(SdkVersion::Any)
     }
 
-    fn find_double_release(keys: (&str, &str)) -> io::Result<Option<Self>> {
-        let res = Self::query_reg(keys.0);
-        match res {
-            Ok(None) => Self::query_reg(keys.1),
-            _ => res,
-        }
+    /// Returns installation information for the given Windows 10 SDK build, if it is installed.
+    ///
+    /// `build` is a build number such as `10.0.18362` or `10.0.18362.0`; as with `find_all_v10`,
+    /// the returned `product_version` omits a zero fourth component. The build is installed if
+    /// the folder recorded by `KitsRoot10` under `Windows Kits\Installed Roots` has `Include`
+    /// and `Lib` subfolders for it, so `include_dir` and `lib_dir` always use that build's
+    /// folders. Unlike `find_preferring_build`, this never falls back to another build.
+    ///
+    /// # Errors
+    ///
+    /// Returns `FindSdkError::Registry` if the registry cannot be read.
+    pub fn find_v10_build(build: &str) -> Result<Option<Self>, FindSdkError> {
+        let Some(root) = registry::kits_root10(RegistryHive::LocalMachine, RegistryView::Any)?
+        else {
+            return Ok(None);
+        };
+        let info = Self {
+            installation_folder: normalize_folder(root),
+            product_name: None,
+            product_version: search::trim_zero_revision(build),
+            bin_dir_from_env: None,
+            flavor: None,
+            raw_values: BTreeMap::new(),
+            registry_last_write: None,
+        };
+        let folder = info.build_folder_name();
+        let installed = ["Include", "Lib"]
+            .iter()
+            .all(|name| info.installation_folder.join(name).join(&folder).is_dir());
+        Ok(if installed { Some(info) } else { None })
     }
 
-    /// Returns installation information for a Windows SDK from environment variables, if present.
-    fn query_env() -> Option<Self> {
-        env::var_os("WindowsSdkDir")
-            .and_then(|install_dir| {
-                env::var_os("WindowsSdkVersion").map(|version| (install_dir, version))
-            })
-            .map(|(install_dir, version)| {
-                let ver = version
-                    .into_string()
-                    .map(|s| {
-                        s.split(r".0\")
-                            .next()
-                            .expect("`str::split` failed")
-                            .to_owned()
-                    })
-                    .expect("`WindowsSdkVersion` was not valid UTF-8");
-                Self {
-                    installation_folder: Path::new(&install_dir).to_owned(),
-                    product_name: None,
-                    product_version: ver,
-                }
-            })
+    /// Returns installation information for the first Windows SDK installation that satisfies
+    /// `predicate`.
+    ///
+    /// Installations are tested in the same order that `find_all` lists them.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the search fails, as for `SdkSearch::find`.
+    pub fn find_where<F: Fn(&Self) -> bool>(predicate: F) -> Result<Option<Self>, FindSdkError> {
+        Ok(Self::find_all()?.into_iter().find(|info| predicate(info)))
     }
 
-    fn query_reg<P: AsRef<OsStr>>(subkey: P) -> io::Result<Option<Self>> {
-        RegKey::predef(HKEY_LOCAL_MACHINE)
-            .open_subkey_with_flags(subkey, KEY_QUERY_VALUE | KEY_WOW64_32KEY)
-            .map(|key| {
-                // If deserialization fails, the key might not have been deleted correctly.
-                if let Ok(info) = key.decode() {
-                    Some(info)
-                } else {
-                    None
-                }
-            })
-            .or_else(|e| {
-                if e.kind() == ErrorKind::NotFound {
-                    Ok(None)
-                } else {
-                    Err(e)
-                }
-            })
+    fn is_same_installation(&self, other: &Self) -> bool {
+        self.installation_folder == other.installation_folder
+            && self.product_version == other.product_version
     }
 
+    #[must_use]
     /// Returns the filesystem path to where a Windows SDK instance is installed.
     pub fn installation_folder(&self) -> &Path {
         &self.installation_folder
     }
 
+    #[must_use]
+    /// Returns whether the installation folder of a Windows SDK instance exists.
+    ///
+    /// Uninstalled SDKs sometimes leave registry keys behind that point to deleted folders.
+    pub fn is_present(&self) -> bool {
+        self.installation_folder.is_dir()
+    }
+
+    #[must_use]
+    /// Returns the drive that the installation folder is on, e.g. `C:` for
+    /// `C:\Program Files (x86)\Windows Kits\10`.
+    ///
+    /// For folders on a network share, such as `\\server\share\Windows Kits\10`, this is the
+    /// share, `\\server\share`. The `\\?\` prefix used by canonical paths is ignored. Returns
+    /// `None` if the folder is not an absolute path on a drive or share.
+    pub fn install_root_drive(&self) -> Option<String> {
+        drive_prefix(self.installation_folder.to_str()?)
+    }
+
+    /// Like `installation_folder`, but checks that the folder exists.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error of kind `io::ErrorKind::NotFound` if the installation folder does not
+    /// exist.
+    pub fn installation_folder_checked(&self) -> io::Result<&Path> {
+        if self.is_present() {
+            Ok(&self.installation_folder)
+        } else {
+            Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                format!(
+                    "Windows SDK installation folder `{}` does not exist",
+                    self.installation_folder.display()
+                ),
+            ))
+        }
+    }
+
+    /// Checks that this installation can be used, returning an error describing the first
+    /// check that fails.
+    ///
+    /// The checks are, in order:
+    ///
+    /// 1. The installation folder exists, as for `installation_folder_checked`.
+    /// 2. The folder returned by `include_dir` exists. Otherwise, an error of kind
+    ///    `io::ErrorKind::NotFound` is returned.
+    /// 3. `product_version` is a numeric version number, as accepted by `version`. Otherwise, an
+    ///    error of kind `io::ErrorKind::InvalidData` is returned.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error describing the first check that fails.
+    pub fn validate(&self) -> io::Result<()> {
+        let _ = self.installation_folder_checked()?;
+        let include = self.include_dir();
+        if !include.is_dir() {
+            return Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                format!(
+                    "Windows SDK include folder `{}` does not exist",
+                    include.display()
+                ),
+            ));
+        }
+        if self.version().is_none() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "Windows SDK version number `{}` is not numeric",
+                    self.product_version
+                ),
+            ));
+        }
+        Ok(())
+    }
+
+    #[must_use]
     /// Returns the human-readable name of a Windows SDK instance.
     pub fn product_name(&self) -> Option<&str> {
-        self.product_name.as_ref().map(|s| s.as_ref())
+        self.product_name.as_ref().map(AsRef::as_ref)
+    }
+
+    #[must_use]
+    /// Returns the human-readable name of a Windows SDK instance, or one made up from its version
+    /// if none was recorded, e.g. `Windows 10 SDK (10.0.19041.0)`.
+    ///
+    /// Installations found via environment variables never have a recorded name.
+    pub fn product_name_or_default(&self) -> String {
+        self.display_name().into_owned()
+    }
+
+    #[must_use]
+    /// Like `product_name_or_default`, but borrows the recorded name instead of copying it.
+    pub fn display_name(&self) -> Cow<'_, str> {
+        match self.product_name {
+            Some(ref name) => Cow::Borrowed(name),
+            None => Cow::Owned(self.default_name()),
+        }
+    }
+
+    #[must_use]
+    /// Returns a one-line description of this installation, for logging or reporting which SDK
+    /// was found.
+    ///
+    /// This combines the recorded product name (or the name of its release, if none was
+    /// recorded), the version number and the installation folder, e.g.
+    /// `Windows 10 SDK 10.0.19041.0 at C:\Program Files (x86)\Windows Kits\10`.
+    pub fn summary(&self) -> String {
+        let name = match self.product_name {
+            Some(ref name) => name.clone(),
+            None => self.release_name(),
+        };
+        format!(
+            "{} {} at {}",
+            name,
+            self.formatted_version(),
+            self.installation_folder.display()
+        )
+    }
+
+    /// Makes up a human-readable name from the version number, for when none was recorded.
+    fn default_name(&self) -> String {
+        format!("{} ({})", self.release_name(), self.formatted_version())
+    }
+
+    /// Returns the name of the release that this instance belongs to, e.g. `Windows SDK v8.1A`.
+    fn release_name(&self) -> String {
+        match self.release() {
+            Some(SdkVersion::V10_0) => "Windows 10 SDK".to_owned(),
+            Some(release) => {
+                let suffix = match self.flavor {
+                    Some(Flavor::WithA) => "A",
+                    _ => "",
+                };
+                format!("Windows SDK {release}{suffix}")
+            }
+            None => "Windows SDK".to_owned(),
+        }
+    }
+
+    /// Returns the version number with all four components if it is numeric, or as it was
+    /// recorded otherwise.
+    fn formatted_version(&self) -> String {
+        self.version()
+            .map_or_else(|| self.product_version.clone(), |v| v.to_string())
     }
 
+    #[must_use]
     /// Returns the version number of a Windows SDK instance.
     pub fn product_version(&self) -> &str {
         &self.product_version
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use {SdkInfo, SdkVersion};
+    #[must_use]
+    /// Returns the version number of a Windows SDK instance as a `Version`.
+    ///
+    /// Returns `None` if the version number is not numeric; see `Version::from_str` for the
+    /// accepted format. The original string is still available from `product_version`.
+    pub fn version(&self) -> Option<Version> {
+        self.product_version.parse().ok()
+    }
 
-    #[test]
-    fn any() {
-        let _ = SdkInfo::find(SdkVersion::Any)
-            .expect("could not retrieve Windows SDK info from registry")
-            .expect("Windows SDK is not installed");
+    #[must_use]
+    /// Returns the dot-separated components of the version number, e.g. `[8, 1]` for `8.1`.
+    ///
+    /// Unlike `version`, missing components are not filled in, and there may be more than four.
+    /// A single `A` or `a` suffix on the last component is ignored. Returns an empty vector if
+    /// any component is not a number.
+    pub fn product_version_components(&self) -> Vec<u32> {
+        let version = &self.product_version;
+        let version = if version.ends_with(['A', 'a']) {
+            &version[..version.len() - 1]
+        } else {
+            version
+        };
+        version
+            .split('.')
+            .map(str::parse)
+            .collect::<Result<_, _>>()
+            .unwrap_or_default()
     }
 
-    #[test]
-    fn winsdk_env() {
-        let _ = SdkInfo::find(SdkVersion::Env)
-            .unwrap_or_else(|_| unreachable!())
-            .expect("environment does not specify a Windows SDK installation");
+    #[must_use]
+    /// Returns whether this installation belongs to a release newer than `version`.
+    ///
+    /// The release is inferred from the major and minor components of the version number, e.g.
+    /// `10.0.19041` belongs to `SdkVersion::V10_0`, so this also works for installations found
+    /// via environment variables. Installations whose version number does not belong to a known
+    /// release are never newer than anything.
+    pub fn is_newer_than(&self, version: SdkVersion) -> bool {
+        self.release().is_some_and(|release| release > version)
     }
 
-    #[test]
-    fn winsdk_10_0() {
-        let _ = SdkInfo::find(SdkVersion::V10_0)
-            .expect("could not retrieve Windows 10 SDK info from registry")
-            .expect("Windows 10 SDK is not installed");
+    #[must_use]
+    /// Returns whether this installation belongs to `version` or a newer release.
+    ///
+    /// The release is inferred as for `is_newer_than`. Every installation is at least
+    /// `SdkVersion::Any` or `SdkVersion::Env`, which give no minimum, as with
+    /// `SdkSearch::min_version`.
+    pub fn is_at_least(&self, version: SdkVersion) -> bool {
+        version <= SdkVersion::Env || self.release().is_some_and(|release| release >= version)
     }
 
-    #[test]
-    fn winsdk_8_1() {
-        let _ = SdkInfo::find(SdkVersion::V8_1)
-            .expect("could not retrieve Windows 8.1 SDK info from registry")
-            .expect("Windows 8.1 SDK is not installed");
+    #[must_use]
+    /// Returns whether this installation belongs to the release `version`.
+    ///
+    /// The release is inferred as for `is_newer_than`, so an installation found via environment
+    /// variables matches the release its version number belongs to. `SdkVersion::Any` and
+    /// `SdkVersion::Env` do not name a release, so every installation matches them.
+    pub fn matches_version(&self, version: SdkVersion) -> bool {
+        version <= SdkVersion::Env || self.release() == Some(version)
+    }
+
+    #[must_use]
+    /// Compares two installations by their numeric version number alone.
+    ///
+    /// Version numbers that cannot be parsed compare equal to each other and less than all
+    /// others. Unlike `cmp`, this ignores everything else, so e.g. the same build installed in
+    /// two folders compares equal.
+    pub fn cmp_version(&self, other: &Self) -> Ordering {
+        self.version().cmp(&other.version())
+    }
+
+    #[must_use]
+    /// Returns a key for sorting installations by their numeric version number.
+    ///
+    /// The key holds the major, minor, build and revision components, in that order. Version
+    /// numbers that cannot be parsed give `(0, 0, 0, 0)`, consistent with `cmp_version`.
+    pub fn ordering_key(&self) -> (u32, u32, u32, u32) {
+        let v = self.version().unwrap_or_default();
+        (v.major, v.minor, v.build, v.revision)
+    }
+
+    #[must_use]
+    /// Returns which flavour of a release that comes in two flavours was found.
+    ///
+    /// This is `None` for releases that only come in one flavour, such as v10.0, and for
+    /// installations specified by environment variables.
+    pub fn flavor(&self) -> Option<Flavor> {
+        self.flavor
+    }
+
+    #[must_use]
+    /// Returns every value of the registry key that this installation was read from, for
+    /// diagnostic purposes.
+    ///
+    /// Numeric values are formatted in decimal, and values of other non-string types are omitted.
+    /// This is empty if the installation was not read from a `Microsoft SDKs\Windows` key, e.g.
+    /// if it was specified by environment variables.
+    pub fn raw_values(&self) -> &BTreeMap<String, String> {
+        &self.raw_values
+    }
+
+    #[must_use]
+    /// Returns when the registry key that this installation was read from was last written to.
+    ///
+    /// This usually tells when the SDK was installed or last updated, which can differ from the
+    /// order of version numbers. This is `None` for installations that were not read from a
+    /// `Microsoft SDKs\Windows` key, or if the time could not be read.
+    pub fn registry_last_write(&self) -> Option<SystemTime> {
+        self.registry_last_write
+    }
+
+    #[must_use]
+    /// Returns the `ManifestEnabled` value of the registry key that this installation was read
+    /// from, if it has one.
+    ///
+    /// This is `None` if the value is absent or is not a number, including for installations that
+    /// were not read from the registry.
+    pub fn manifest_enabled(&self) -> Option<bool> {
+        self.raw_values
+            .get("ManifestEnabled")
+            .and_then(|value| value.parse::<u32>().ok())
+            .map(|value| value != 0)
+    }
+
+    #[must_use]
+    /// Returns the versioned `bin` folder given by the `WindowsSdkVerBinPath` environment
+    /// variable, if this instance was specified by environment variables that include it.
+    ///
+    /// Visual Studio developer command prompts set this to the folder holding the tools for the
+    /// selected Windows 10 SDK build, e.g. `bin\10.0.17763.0\` within the installation folder.
+    pub fn bin_dir_from_env(&self) -> Option<&Path> {
+        self.bin_dir_from_env.as_deref()
+    }
+
+    #[must_use]
+    /// Combines this installation, as specified by environment variables, with `registry`, an
+    /// installation read from the registry.
+    ///
+    /// The result keeps this installation's folder and `bin_dir_from_env`. The product name,
+    /// flavour and raw values of `registry` are used, falling back to this installation's
+    /// product name if `registry` has none. The version number of `registry` is only used if it
+    /// refines this one, e.g. `8.1.25984` for `8.1`. This is the same policy as
+    /// `SdkSearch::enrich_env`, but leaves it to the caller to decide which installations belong
+    /// together.
+    pub fn merge_env_with(&self, registry: &SdkInfo) -> SdkInfo {
+        search::merge_env_info(self.clone(), registry.clone())
+    }
+
+    #[must_use]
+    /// Returns which of the installation folder, product name and version number differ between
+    /// this installation and `other`, in that order.
+    ///
+    /// This is useful for telling what changed between a cached result and a fresh one, e.g.
+    /// after updating an SDK. Installation folders are compared ignoring case and trailing
+    /// separators, as Windows does. Other details, such as raw registry values, are not compared.
+    pub fn diff(&self, other: &Self) -> Vec<FieldDiff> {
+        let mut diffs = Vec::new();
+        if !search::same_folder(&self.installation_folder, &other.installation_folder) {
+            diffs.push(FieldDiff::InstallationFolder);
+        }
+        if self.product_name != other.product_name {
+            diffs.push(FieldDiff::ProductName);
+        }
+        if self.product_version != other.product_version {
+            diffs.push(FieldDiff::ProductVersion);
+        }
+        diffs
+    }
+
+    /// Returns the build numbers of every Windows 10 SDK build in a Windows SDK instance, newest
+    /// first.
+    ///
+    /// Several builds of the Windows 10 SDK (e.g. `10.0.17763.0` and `10.0.19041.0`) can be
+    /// installed side by side; each one has its own folder under `Include`, `Lib` and `bin`. This
+    /// method lists the folders found under `Include`. Older SDKs are not split by build, so an
+    /// empty list is returned for them.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the `Include` folder cannot be read.
+    pub fn build_numbers(&self) -> io::Result<Vec<String>> {
+        if !self.is_v10() {
+            return Ok(Vec::new());
+        }
+        let mut builds = Vec::new();
+        for entry in fs::read_dir(self.installation_folder.join("Include"))? {
+            let entry = entry?;
+            if !entry.file_type()?.is_dir() {
+                continue;
+            }
+            if let Ok(name) = entry.file_name().into_string() {
+                if let Ok(version) = name.parse::<Version>() {
+                    builds.push((version, name));
+                }
+            }
+        }
+        builds.sort_by_key(|&(version, _)| Reverse(version));
+        Ok(builds.into_iter().map(|(_, name)| name).collect())
+    }
+
+    /// Returns the newest Windows 10 SDK build installed in this instance's installation folder.
+    ///
+    /// This is the first of `build_numbers`, and might be newer than `product_version`, which the
+    /// registry does not always keep up to date. Returns `None` if no builds are installed, or for
+    /// SDKs older than v10.0.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the `Include` folder cannot be read, as for `build_numbers`.
+    pub fn latest_build_dir(&self) -> io::Result<Option<String>> {
+        Ok(self.build_numbers()?.into_iter().next())
+    }
+
+    #[must_use]
+    /// Returns the path to the root of a Windows SDK instance's header files.
+    ///
+    /// For the Windows 10 SDK this is the `Include` subfolder for the build given by
+    /// `product_version`, or the `Include` folder itself if there is no such subfolder. For older
+    /// SDKs this is always the `Include` folder.
+    pub fn include_dir(&self) -> PathBuf {
+        self.layout_dir("Include")
+    }
+
+    #[must_use]
+    /// Returns the path to the root of a Windows SDK instance's library files.
+    ///
+    /// This follows the same rules as `include_dir`, using the `Lib` folder instead of `Include`.
+    /// Note that the returned path might not exist if the SDK was only partially installed.
+    pub fn lib_dir(&self) -> PathBuf {
+        self.layout_dir("Lib")
+    }
+
+    #[must_use]
+    /// Returns the path to the root of a Windows SDK instance's tools.
+    ///
+    /// This follows the same rules as `include_dir`, using the `bin` folder instead of `Include`.
+    /// Tools for each architecture are kept in `<arch>` subfolders; see `tool_path`.
+    pub fn bin_dir(&self) -> PathBuf {
+        self.layout_dir("bin")
+    }
+
+    #[must_use]
+    /// Returns whether this SDK has the headers needed to compile C or C++ code.
+    ///
+    /// This checks for `windows.h` in `um_include_dir`, or for SDKs older than v8.0, directly in
+    /// `include_dir`. Some installations only include tools, and have no headers at all.
+    pub fn has_headers(&self) -> bool {
+        self.um_include_dir()
+            .unwrap_or_else(|| self.include_dir())
+            .join("windows.h")
+            .is_file()
+    }
+
+    #[must_use]
+    /// Returns whether this SDK has a header with the given name, e.g. `d3d12.h`.
+    ///
+    /// `name` is relative to an include folder, so it may name a subfolder, as in `winrt/base.h`.
+    /// The header is looked for in `um_include_dir`, `shared_include_dir`, `ucrt_include_dir`,
+    /// `winrt_include_dir` and `cppwinrt_include_dir`, or for SDKs older than v8.0, directly in
+    /// `include_dir`.
+    pub fn contains_header(&self, name: &str) -> bool {
+        let mut include = vec![
+            self.um_include_dir(),
+            self.shared_include_dir(),
+            self.ucrt_include_dir(),
+            self.winrt_include_dir(),
+            self.cppwinrt_include_dir(),
+        ];
+        if include[0].is_none() {
+            include.push(existing_dir(self.include_dir()));
+        }
+        include
+            .into_iter()
+            .flatten()
+            .any(|dir| dir.join(name).is_file())
+    }
+
+    #[must_use]
+    /// Returns the path to the Win32 (user-mode) header files, if they exist.
+    ///
+    /// Starting with v8.0, SDK headers are split into `um`, `shared` and `winrt` subfolders of the
+    /// include folder. Older SDKs keep all headers directly in `include_dir`, so this always
+    /// returns `None` for them.
+    pub fn um_include_dir(&self) -> Option<PathBuf> {
+        existing_dir(self.include_dir().join("um"))
+    }
+
+    #[must_use]
+    /// Returns the path to the header files shared between user mode and kernel mode, if they
+    /// exist.
+    ///
+    /// As with `um_include_dir`, this always returns `None` for SDKs older than v8.0.
+    pub fn shared_include_dir(&self) -> Option<PathBuf> {
+        existing_dir(self.include_dir().join("shared"))
+    }
+
+    #[must_use]
+    /// Returns the path to the Windows Runtime header files, if they exist.
+    ///
+    /// As with `um_include_dir`, this always returns `None` for SDKs older than v8.0.
+    pub fn winrt_include_dir(&self) -> Option<PathBuf> {
+        existing_dir(self.include_dir().join("winrt"))
+    }
+
+    #[must_use]
+    /// Returns the path to the Win32 (user-mode) library files for the given architecture, if they
+    /// exist.
+    ///
+    /// Starting with v8.0, SDK libraries are kept in `um\<arch>` subfolders of the library
+    /// folder (for v8.0 and v8.1, within a further `win8` or `winv6.3` subfolder respectively).
+    /// Older SDKs keep their libraries directly in `lib_dir`, so this always returns `None` for
+    /// them.
+    pub fn um_lib_dir(&self, arch: Arch) -> Option<PathBuf> {
+        let lib = self.lib_dir();
+        let lib = match self.release() {
+            Some(SdkVersion::V8_1) => lib.join("winv6.3"),
+            Some(SdkVersion::V8_0) => lib.join("win8"),
+            _ => lib,
+        };
+        existing_dir(lib.join("um").join(arch.dir_name()))
+    }
+
+    #[must_use]
+    /// Returns the path to the Universal CRT header files, if they exist.
+    ///
+    /// The Universal CRT is only split out of the main headers in the Windows 10 SDK, so this
+    /// always returns `None` for older SDKs.
+    pub fn ucrt_include_dir(&self) -> Option<PathBuf> {
+        self.v10_dir(self.include_dir().join("ucrt"))
+    }
+
+    #[must_use]
+    /// Returns the path to the Universal CRT library files for the given architecture, if they
+    /// exist.
+    ///
+    /// As with `ucrt_include_dir`, this always returns `None` for SDKs older than v10.0.
+    pub fn ucrt_lib_dir(&self, arch: Arch) -> Option<PathBuf> {
+        self.v10_dir(self.lib_dir().join("ucrt").join(arch.dir_name()))
+    }
+
+    #[must_use]
+    /// Returns the path to the C++/WinRT projection headers, if they exist.
+    ///
+    /// C++/WinRT is only part of the Windows 10 SDK, so this always returns `None` for older SDKs.
+    pub fn cppwinrt_include_dir(&self) -> Option<PathBuf> {
+        self.v10_dir(self.include_dir().join("cppwinrt"))
+    }
+
+    #[must_use]
+    /// Returns the path to the C++/WinRT compiler, `cppwinrt.exe`, for the given architecture, if
+    /// it exists.
+    ///
+    /// As with `cppwinrt_include_dir`, this always returns `None` for SDKs older than v10.0.
+    pub fn cppwinrt_tool(&self, arch: Arch) -> Option<PathBuf> {
+        if self.is_v10() {
+            self.tool_path("cppwinrt.exe", arch)
+        } else {
+            None
+        }
+    }
+
+    #[must_use]
+    /// Returns the path to the Windows Runtime API contract metadata (`.winmd` files), if it
+    /// exists.
+    ///
+    /// This is `References\<build>` for the current Windows 10 SDK build, or `References` for
+    /// builds that are not split into versioned folders. Windows Runtime metadata is only kept
+    /// separately in the Windows 10 SDK, so this always returns `None` for older SDKs.
+    pub fn winmd_references_dir(&self) -> Option<PathBuf> {
+        self.v10_dir(self.layout_dir("References"))
+    }
+
+    #[must_use]
+    /// Returns the path to the merged Windows Runtime metadata, e.g. `Windows.winmd`, if it
+    /// exists.
+    ///
+    /// As with `winmd_references_dir`, this is `UnionMetadata\<build>` or `UnionMetadata`, and
+    /// always `None` for SDKs older than v10.0.
+    pub fn union_metadata_dir(&self) -> Option<PathBuf> {
+        self.v10_dir(self.layout_dir("UnionMetadata"))
+    }
+
+    #[must_use]
+    /// Returns the path to the Debugging Tools for Windows (e.g. `cdb.exe` and `windbg.exe`) for
+    /// the given architecture, if they are installed.
+    pub fn debuggers_dir(&self, arch: Arch) -> Option<PathBuf> {
+        existing_dir(
+            self.installation_folder
+                .join("Debuggers")
+                .join(arch.dir_name()),
+        )
+    }
+
+    #[must_use]
+    /// Returns the path to the Windows Performance Toolkit (e.g. `xperf.exe` and `wpa.exe`), if it
+    /// is installed.
+    pub fn wpt_dir(&self) -> Option<PathBuf> {
+        existing_dir(self.installation_folder.join("Windows Performance Toolkit"))
+    }
+
+    #[must_use]
+    /// Returns the path to the `MSBuild` property sheets that integrate this SDK with Visual C++
+    /// projects, if they are installed.
+    ///
+    /// These are kept in `DesignTime\CommonConfiguration\Neutral`, e.g. `Windows.props`, which
+    /// project generators can import instead of assuming where the SDK is installed. Some Windows
+    /// 10 SDKs keep them in a `build` folder instead, with a subfolder for each build; if so, the
+    /// folder for the current build (or `build` itself, if it has none) is returned. SDKs older
+    /// than v8.0 do not have them.
+    pub fn platform_props_dir(&self) -> Option<PathBuf> {
+        existing_dir(self.layout_dir("build")).or_else(|| {
+            existing_dir(
+                self.installation_folder
+                    .join("DesignTime")
+                    .join("CommonConfiguration")
+                    .join("Neutral"),
+            )
+        })
+    }
+
+    #[must_use]
+    /// Returns the path to the Windows App Certification Kit, if it is installed.
+    ///
+    /// The kit is an optional component of the Windows 10 SDK, and is used to validate apps
+    /// before submitting them to the Microsoft Store.
+    pub fn wack_dir(&self) -> Option<PathBuf> {
+        existing_dir(self.installation_folder.join("App Certification Kit"))
+    }
+
+    #[must_use]
+    /// Returns the path to `appcert.exe`, the command-line interface to the Windows App
+    /// Certification Kit, if it is installed.
+    pub fn appcert_tool(&self) -> Option<PathBuf> {
+        self.wack_dir()
+            .map(|dir| dir.join("appcert.exe"))
+            .filter(|path| path.is_file())
+    }
+
+    #[must_use]
+    /// Returns the path to an HLSL shader compiler for the given architecture, if it exists.
+    ///
+    /// The compiler is looked for as in `tool_path`. Not every SDK includes both compilers, so
+    /// this returns `None` for any that are missing.
+    pub fn shader_compiler(&self, which: ShaderCompiler, arch: Arch) -> Option<PathBuf> {
+        self.tool_path(which.file_name(), arch)
+    }
+
+    #[must_use]
+    /// Returns the path to a tool for the given architecture, if it exists.
+    ///
+    /// `tool` is the file name of the tool, e.g. `signtool.exe`; if it has no extension, `.exe` is
+    /// assumed. Tools are looked for in `bin\<build>\<arch>` for the current Windows 10 SDK build,
+    /// then in `bin\<arch>`. SDKs prior to v8.0 keep their x86 tools directly in `bin`, so that
+    /// is also tried for `Arch::X86`.
+    pub fn tool_path(&self, tool: &str, arch: Arch) -> Option<PathBuf> {
+        let file_name = tool_file_name(tool);
+        self.bin_dirs(arch)
+            .into_iter()
+            .map(|dir| dir.join(&file_name))
+            .find(|path| path.is_file())
+    }
+
+    #[must_use]
+    /// Returns the path to a tool that can run on this machine, along with its architecture, if it
+    /// exists.
+    ///
+    /// The tool is looked for as in `tool_path`, trying the host architecture first, then other
+    /// architectures that it can run: x86 on x64, and x64 and x86 on ARM64. If the host
+    /// architecture is not one supported by the Windows SDK, x64 and x86 are tried.
+    pub fn find_tool(&self, tool: &str) -> Option<(Arch, PathBuf)> {
+        let candidates: &[Arch] = match Arch::host() {
+            Some(Arch::X64) | None => &[Arch::X64, Arch::X86],
+            Some(Arch::X86) => &[Arch::X86],
+            Some(Arch::Arm64) => &[Arch::Arm64, Arch::X64, Arch::X86],
+            Some(Arch::Arm) => &[Arch::Arm],
+        };
+        candidates
+            .iter()
+            .find_map(|arch| self.tool_path(tool, *arch).map(|path| (*arch, path)))
+    }
+
+    /// Returns the file version of a tool, as recorded in its version-info resource.
+    ///
+    /// `path` is usually one returned by `tool_path` or `find_tool`. This can differ from the
+    /// SDK's own version number, and lets callers require a minimum build of a tool such as
+    /// `signtool.exe`. Returns `Ok(None)` if the file has no version-info resource.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read.
+    pub fn tool_file_version(&self, path: &Path) -> io::Result<Option<Version>> {
+        pe::file_version(path)
+    }
+
+    #[must_use]
+    /// Returns whether a tool exists for any architecture.
+    ///
+    /// This is a shorthand for calling `tool_path` with each architecture in `Arch::all`.
+    pub fn exists_tool(&self, tool: &str) -> bool {
+        Arch::all()
+            .iter()
+            .any(|arch| self.tool_path(tool, *arch).is_some())
+    }
+
+    /// Returns the path of every executable under the `bin` folder, relative to the installation
+    /// folder and sorted.
+    ///
+    /// This is meant for diagnosing why a tool could not be found, e.g. by listing the tools that
+    /// an installation actually has. Only four levels of subfolders of `bin` are searched.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the `bin` folder, or any of its subfolders, cannot be read.
+    pub fn relative_tool_paths(&self) -> io::Result<Vec<PathBuf>> {
+        let mut paths = Vec::new();
+        find_executables(
+            &self.installation_folder.join("bin"),
+            MAX_TOOL_DEPTH,
+            &mut paths,
+        )?;
+        let mut paths: Vec<PathBuf> = paths
+            .into_iter()
+            .filter_map(|path| {
+                path.strip_prefix(&self.installation_folder)
+                    .ok()
+                    .map(Path::to_path_buf)
+            })
+            .collect();
+        paths.sort();
+        Ok(paths)
+    }
+
+    /// Returns the environment variables that make this SDK available to the Visual C++ compiler
+    /// and linker targeting the given architecture.
+    ///
+    /// The map contains `INCLUDE`, `LIB` and `PATH`, each holding a `;`-separated list of the
+    /// folders that this SDK contributes to it; these are meant to be prepended to any existing
+    /// value. Folders that do not exist are left out, as are variables with no folders.
+    ///
+    /// `INCLUDE` lists the `um`, `shared`, `ucrt` and `winrt` header folders, and `LIB` lists the
+    /// `um` and `ucrt` library folders. SDKs older than v8.0 do not have these, so their
+    /// `include_dir` and `lib_dir` (or its `<arch>` subfolder for architectures other than x86)
+    /// are used instead. `PATH` lists the folders that `tool_path` searches.
+    pub fn environment_vars(&self, arch: Arch) -> BTreeMap<String, String> {
+        let mut include = vec![
+            self.um_include_dir(),
+            self.shared_include_dir(),
+            self.ucrt_include_dir(),
+            self.winrt_include_dir(),
+        ];
+        let mut lib = vec![self.um_lib_dir(arch), self.ucrt_lib_dir(arch)];
+        if include[0].is_none() {
+            include.push(existing_dir(self.include_dir()));
+        }
+        if lib[0].is_none() {
+            lib.push(self.legacy_lib_dir(arch));
+        }
+        let path = self.bin_dirs(arch).into_iter().map(existing_dir).collect();
+        let mut vars = BTreeMap::new();
+        for (name, dirs) in [("INCLUDE", include), ("LIB", lib), ("PATH", path)] {
+            let dirs: Vec<_> = dirs
+                .into_iter()
+                .flatten()
+                .map(|dir| dir.to_string_lossy().into_owned())
+                .collect();
+            if !dirs.is_empty() {
+                let _ = vars.insert(name.to_owned(), dirs.join(";"));
+            }
+        }
+        vars
+    }
+
+    /// Prints the Cargo directives needed to link against this SDK's libraries for the given
+    /// architecture.
+    ///
+    /// This writes a `cargo:rustc-link-search=native=<dir>` line to standard output for each of
+    /// `um_lib_dir` and `ucrt_lib_dir` that exists, or for SDKs older than v8.0, for the library
+    /// folder used by `environment_vars`. Nothing is printed for folders that do not exist.
+    /// Cargo only acts on these lines when they are printed by a build script, so this should
+    /// only be called from within `build.rs`.
+    pub fn print_cargo_directives(&self, arch: Arch) {
+        for directive in self.cargo_directives(arch) {
+            println!("{directive}");
+        }
+    }
+
+    #[must_use]
+    /// Returns whether this SDK has libraries for the given architecture.
+    ///
+    /// This checks that `um_lib_dir` exists, and for the Windows 10 SDK, that `ucrt_lib_dir` also
+    /// exists. SDKs older than v8.0 keep x86 libraries directly in `lib_dir` and others in its
+    /// `<arch>` subfolder, so that folder is checked for them instead.
+    pub fn supports_arch(&self, arch: Arch) -> bool {
+        match self.um_lib_dir(arch) {
+            Some(_) => !self.is_v10() || self.ucrt_lib_dir(arch).is_some(),
+            None => self.um_include_dir().is_none() && self.legacy_lib_dir(arch).is_some(),
+        }
+    }
+
+    /// Returns the folder holding libraries for the given architecture in SDKs older than v8.0, if
+    /// it exists.
+    fn legacy_lib_dir(&self, arch: Arch) -> Option<PathBuf> {
+        existing_dir(match arch {
+            Arch::X86 => self.lib_dir(),
+            _ => self.lib_dir().join(arch.dir_name()),
+        })
+    }
+
+    /// Returns the folders that might hold tools for the given architecture, in the order that
+    /// they should be searched.
+    fn bin_dirs(&self, arch: Arch) -> Vec<PathBuf> {
+        let bin = self.installation_folder.join("bin");
+        let mut dirs = vec![self.bin_dir().join(arch.dir_name())];
+        dirs.push(bin.join(arch.dir_name()));
+        if arch == Arch::X86 {
+            dirs.push(bin);
+        }
+        dirs.dedup();
+        dirs
+    }
+
+    #[must_use]
+    /// Returns the path to a .NET Framework SDK tool (e.g. `gacutil.exe` or `sn.exe`) for the
+    /// given architecture, if it exists.
+    ///
+    /// These tools are kept in a `bin\NETFX <version> Tools` folder, whose version differs between
+    /// SDK releases; if there are several such folders, the newest one that has the tool is used.
+    /// Tools are looked for in its `<arch>` subfolder, or for `Arch::X86`, the folder itself.
+    pub fn netfx_tool_path(&self, tool: &str, arch: Arch) -> Option<PathBuf> {
+        let file_name = tool_file_name(tool);
+        let mut dirs = Vec::new();
+        for entry in fs::read_dir(self.installation_folder.join("bin")).ok()? {
+            let Ok(entry) = entry else {
+                continue;
+            };
+            let Ok(name) = entry.file_name().into_string() else {
+                continue;
+            };
+            if name.starts_with("NETFX") && name.ends_with("Tools") && entry.path().is_dir() {
+                let version = name["NETFX".len()..name.len() - "Tools".len()]
+                    .trim()
+                    .parse::<Version>()
+                    .ok();
+                dirs.push((version, entry.path()));
+            }
+        }
+        dirs.sort_by(|a, b| b.cmp(a));
+        dirs.into_iter()
+            .flat_map(|(_, dir)| {
+                let mut candidates = vec![dir.join(arch.dir_name())];
+                if arch == Arch::X86 {
+                    candidates.push(dir);
+                }
+                candidates
+            })
+            .map(|dir| dir.join(&file_name))
+            .find(|path| path.is_file())
+    }
+
+    /// Returns the `name` subfolder of `installation_folder`, or for the Windows 10 SDK, the
+    /// subfolder within that for the current build if it exists.
+    fn layout_dir(&self, name: &str) -> PathBuf {
+        let root = self.installation_folder.join(name);
+        if self.is_v10() {
+            let versioned = root.join(self.build_folder_name());
+            if versioned.is_dir() {
+                return versioned;
+            }
+        }
+        root
+    }
+
+    /// Returns the name of the folders that hold files for the current Windows 10 SDK build.
+    ///
+    /// The registry only records the first three components of a build number (e.g. `10.0.17763`),
+    /// but the folders are named using all four (e.g. `10.0.17763.0`).
+    fn build_folder_name(&self) -> String {
+        if self.product_version.split('.').count() == 3 {
+            format!("{}.0", self.product_version)
+        } else {
+            self.product_version.clone()
+        }
+    }
+
+    /// Returns the build numbers of every Windows 10 SDK build registered in the registry under
+    /// `Windows Kits\Installed Roots`, newest first.
+    ///
+    /// Unlike `build_numbers`, this does not look at the filesystem. Older SDKs are not split by
+    /// build, so an empty list is returned for them.
+    ///
+    /// # Errors
+    ///
+    /// Returns `FindSdkError::Registry` if the registry cannot be read.
+    pub fn registered_builds(&self) -> Result<Vec<String>, FindSdkError> {
+        if self.is_v10() {
+            registry::installed_v10_builds(RegistryHive::LocalMachine, RegistryView::Any)
+        } else {
+            Ok(Vec::new())
+        }
+    }
+
+    /// Returns `dir` if this is a Windows 10 SDK instance and `dir` exists.
+    fn v10_dir(&self, dir: PathBuf) -> Option<PathBuf> {
+        if self.is_v10() {
+            existing_dir(dir)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the lines printed by `print_cargo_directives`.
+    fn cargo_directives(&self, arch: Arch) -> Vec<String> {
+        let mut lib = vec![self.um_lib_dir(arch), self.ucrt_lib_dir(arch)];
+        if lib[0].is_none() {
+            lib.push(self.legacy_lib_dir(arch));
+        }
+        lib.into_iter()
+            .flatten()
+            .map(|dir| format!("cargo:rustc-link-search=native={}", dir.display()))
+            .collect()
+    }
+
+    /// Returns the release that this instance belongs to, based on its version number.
+    fn release(&self) -> Option<SdkVersion> {
+        self.version().and_then(|v| SdkVersion::from_version(&v))
+    }
+
+    fn is_v10(&self) -> bool {
+        self.product_version.starts_with("10.")
+    }
+}
+
+impl Ord for SdkInfo {
+    /// Compares installations by their numeric version number, then by installation folder.
+    ///
+    /// Installations whose version number cannot be parsed compare less than all others, so they
+    /// come last when sorting newest first.
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.cmp_version(other)
+            .then_with(|| self.installation_folder.cmp(&other.installation_folder))
+            .then_with(|| self.product_version.cmp(&other.product_version))
+            .then_with(|| self.product_name.cmp(&other.product_name))
+            .then_with(|| self.bin_dir_from_env.cmp(&other.bin_dir_from_env))
+            .then_with(|| self.flavor.cmp(&other.flavor))
+            .then_with(|| self.raw_values.cmp(&other.raw_values))
+            .then_with(|| self.registry_last_write.cmp(&other.registry_last_write))
+    }
+}
+
+impl PartialOrd for SdkInfo {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl From<SdkInfo> for PathBuf {
+    /// Returns the installation folder, consuming the `SdkInfo`.
+    fn from(info: SdkInfo) -> Self {
+        info.installation_folder
+    }
+}
+
+impl<'a> From<&'a SdkInfo> for PathBuf {
+    /// Returns a copy of the installation folder.
+    fn from(info: &'a SdkInfo) -> Self {
+        info.installation_folder.clone()
+    }
+}
+
+/// Returns the file name of a tool, adding an `.exe` extension if it has none.
+fn tool_file_name(tool: &str) -> PathBuf {
+    let mut file_name = PathBuf::from(tool);
+    if file_name.extension().is_none() {
+        let _ = file_name.set_extension("exe");
+    }
+    file_name
+}
+
+/// Adds the path of every `.exe` file in `dir` and up to `depth` levels of its subfolders to
+/// `paths`.
+fn find_executables(dir: &Path, depth: usize, paths: &mut Vec<PathBuf>) -> io::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let path = entry.path();
+        if entry.file_type()?.is_dir() {
+            if depth > 0 {
+                find_executables(&path, depth - 1, paths)?;
+            }
+        } else if path
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("exe"))
+        {
+            paths.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// Returns the drive letter or network share that a Windows path starts with, if any.
+///
+/// This parses the path by hand, rather than with `Path::components`, so that it works the same
+/// on every platform.
+fn drive_prefix(path: &str) -> Option<String> {
+    let is_separator = |c: char| c == '\\' || c == '/';
+    let (path, unc) = if let Some(rest) = path.strip_prefix(r"\\?\UNC\") {
+        (rest, true)
+    } else if let Some(rest) = path.strip_prefix(r"\\?\") {
+        (rest, false)
+    } else if path.starts_with(r"\\") || path.starts_with("//") {
+        (&path[2..], true)
+    } else {
+        (path, false)
+    };
+    if unc {
+        let mut parts = path.split(is_separator);
+        return match (parts.next(), parts.next()) {
+            (Some(server), Some(share)) if !server.is_empty() && !share.is_empty() => {
+                Some(format!(r"\\{server}\{share}"))
+            }
+            _ => None,
+        };
+    }
+    let mut chars = path.chars();
+    match (chars.next(), chars.next(), chars.next()) {
+        (Some(letter), Some(':'), Some(c)) if letter.is_ascii_alphabetic() && is_separator(c) => {
+            Some(format!("{}:", letter.to_ascii_uppercase()))
+        }
+        _ => None,
+    }
+}
+
+/// Removes a trailing separator from a folder path, unless it is a root folder such as `C:\`.
+///
+/// The registry records installation folders both with and without a trailing separator, so this
+/// allows paths to the same folder to compare equal.
+fn normalize_folder(folder: PathBuf) -> PathBuf {
+    if let Some(s) = folder.to_str() {
+        if folder.parent().is_some() && (s.ends_with('\\') || s.ends_with('/')) {
+            return PathBuf::from(&s[..s.len() - 1]);
+        }
+    }
+    folder
+}
+
+/// Deserializes an installation folder, normalizing it with `normalize_folder`.
+fn deserialize_folder<'de, D: Deserializer<'de>>(deserializer: D) -> Result<PathBuf, D::Error> {
+    PathBuf::deserialize(deserializer).map(normalize_folder)
+}
+
+/// Returns `dir` if it exists.
+fn existing_dir(dir: PathBuf) -> Option<PathBuf> {
+    if dir.is_dir() {
+        Some(dir)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+    use std::path::PathBuf;
+    use {SdkInfo, SdkSearch, SdkVersion};
+
+    /// Creates installation information for an SDK that need not exist.
+    fn synthetic(installation_folder: &str, product_version: &str) -> SdkInfo {
+        SdkInfo {
+            installation_folder: PathBuf::from(installation_folder),
+            product_name: None,
+            product_version: product_version.to_owned(),
+            bin_dir_from_env: None,
+            flavor: None,
+            raw_values: BTreeMap::new(),
+            registry_last_write: None,
+        }
+    }
+
+    /// Creates a folder with the given subfolders and empty files in the temporary folder,
+    /// replacing any left over from an earlier run, and returns its path.
+    fn temp_layout(name: &str, dirs: &[&str], files: &[&str]) -> PathBuf {
+        use std::env;
+        use std::fs;
+        let root = env::temp_dir().join(name);
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(&root).expect("could not create test folder");
+        for dir in dirs {
+            fs::create_dir_all(root.join(dir)).expect("could not create test folder");
+        }
+        for file in files {
+            let path = root.join(file);
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent).expect("could not create test folder");
+            }
+            fs::write(path, b"").expect("could not create test file");
+        }
+        root
+    }
+
+    #[test]
+    #[cfg(target_os = "windows")]
+    fn any() {
+        let _ = SdkInfo::find(SdkVersion::Any)
+            .expect("could not retrieve Windows SDK info from registry")
+            .expect("Windows SDK is not installed");
+    }
+
+    #[test]
+    fn all() {
+        let all = SdkInfo::find_all().expect("could not retrieve Windows SDK info from registry");
+        let any = SdkInfo::find(SdkVersion::Any)
+            .expect("could not retrieve Windows SDK info from registry");
+        assert_eq!(all.first(), any.as_ref());
+    }
+
+    #[test]
+    fn all_concrete() {
+        use ANY_SEARCH_ORDER;
+        let concrete = SdkVersion::all_concrete();
+        let any: Vec<_> = ANY_SEARCH_ORDER
+            .iter()
+            .copied()
+            .filter(|v| *v != SdkVersion::Env)
+            .collect();
+        assert_eq!(concrete, &any[..]);
+        assert!(!concrete.contains(&SdkVersion::Any));
+        assert!(concrete.windows(2).all(|w| w[0] > w[1]));
+    }
+
+    #[test]
+    fn debuggers_and_wpt_dirs() {
+        use std::fs;
+        use Arch;
+        let root = temp_layout(
+            "find-winsdk-debuggers-wpt",
+            &["Debuggers/x64", "Windows Performance Toolkit"],
+            &[],
+        );
+        let folder = root.to_str().expect("invalid temporary folder");
+        let info = synthetic(folder, "10.0.17763");
+        let debuggers = info.debuggers_dir(Arch::X64);
+        let arm_debuggers = info.debuggers_dir(Arch::Arm);
+        let wpt = info.wpt_dir();
+        fs::remove_dir_all(&root).expect("could not delete test folder");
+        assert_eq!(debuggers, Some(root.join("Debuggers").join("x64")));
+        assert_eq!(arm_debuggers, None);
+        assert_eq!(wpt, Some(root.join("Windows Performance Toolkit")));
+        assert_eq!(info.debuggers_dir(Arch::X64), None);
+        assert_eq!(info.wpt_dir(), None);
+    }
+
+    #[test]
+    fn exists_tool() {
+        use std::fs;
+        let root = temp_layout("find-winsdk-exists-tool", &[], &["bin/x64/rc.exe"]);
+        let info = synthetic(root.to_str().expect("invalid temporary folder"), "8.1");
+        let rc = info.exists_tool("rc.exe");
+        let rc_without_extension = info.exists_tool("rc");
+        let mt = info.exists_tool("mt.exe");
+        fs::remove_dir_all(&root).expect("could not delete test folder");
+        assert!(rc);
+        assert!(rc_without_extension);
+        assert!(!mt);
+        assert!(!info.exists_tool("rc.exe"));
+    }
+
+    #[test]
+    fn manifest_enabled() {
+        let with_value = |value: &str| {
+            let mut info = synthetic(r"C:\find-winsdk", "10.0.17763");
+            let _ = info
+                .raw_values
+                .insert("ManifestEnabled".to_owned(), value.to_owned());
+            info.manifest_enabled()
+        };
+        assert_eq!(with_value("0"), Some(false));
+        assert_eq!(with_value("1"), Some(true));
+        assert_eq!(with_value("x"), None);
+        assert_eq!(
+            synthetic(r"C:\find-winsdk", "10.0.17763").manifest_enabled(),
+            None
+        );
+    }
+
+    #[test]
+    fn supports_arch() {
+        use std::fs;
+        use Arch;
+        let root = temp_layout(
+            "find-winsdk-supports-arch",
+            &[
+                "Include/10.0.17763.0/um",
+                "Lib/10.0.17763.0/um/x64",
+                "Lib/10.0.17763.0/ucrt/x64",
+                "Lib/10.0.17763.0/um/arm64",
+            ],
+            &[],
+        );
+        let info = synthetic(
+            root.to_str().expect("invalid temporary folder"),
+            "10.0.17763",
+        );
+        let supported: Vec<Arch> = Arch::all()
+            .iter()
+            .copied()
+            .filter(|arch| info.supports_arch(*arch))
+            .collect();
+        fs::remove_dir_all(&root).expect("could not delete test folder");
+        assert_eq!(supported, [Arch::X64]);
+        assert!(Arch::all().iter().all(|arch| !info.supports_arch(*arch)));
+    }
+
+    #[test]
+    fn cppwinrt() {
+        use std::fs;
+        use Arch;
+        let root = temp_layout(
+            "find-winsdk-cppwinrt",
+            &["Include/10.0.17763.0/cppwinrt"],
+            &["bin/10.0.17763.0/x64/cppwinrt.exe"],
+        );
+        let folder = root.to_str().expect("invalid temporary folder");
+        let info = synthetic(folder, "10.0.17763");
+        let include = info.cppwinrt_include_dir();
+        let tool = info.cppwinrt_tool(Arch::X64);
+        let x86_tool = info.cppwinrt_tool(Arch::X86);
+        let v8_1 = synthetic(folder, "8.1");
+        let v8_1_tool = v8_1.cppwinrt_tool(Arch::X64);
+        fs::remove_dir_all(&root).expect("could not delete test folder");
+        let build = |name: &str| root.join(name).join("10.0.17763.0");
+        assert_eq!(include, Some(build("Include").join("cppwinrt")));
+        assert_eq!(tool, Some(build("bin").join("x64").join("cppwinrt.exe")));
+        assert_eq!(x86_tool, None);
+        assert_eq!(v8_1_tool, None);
+        assert_eq!(info.cppwinrt_include_dir(), None);
+        assert_eq!(info.cppwinrt_tool(Arch::X64), None);
+    }
+
+    #[test]
+    fn find_tool() {
+        use std::fs;
+        use Arch;
+        let root = temp_layout("find-winsdk-find-tool", &[], &["bin/x86/rc.exe"]);
+        let info = synthetic(root.to_str().expect("invalid temporary folder"), "8.1");
+        let rc = info.find_tool("rc.exe");
+        let mt = info.find_tool("mt.exe");
+        fs::remove_dir_all(&root).expect("could not delete test folder");
+        // Every host except 32-bit Arm can run x86 tools.
+        if Arch::host() != Some(Arch::Arm) {
+            assert_eq!(
+                rc,
+                Some((Arch::X86, root.join("bin").join("x86").join("rc.exe")))
+            );
+        }
+        assert_eq!(mt, None);
+        assert_eq!(info.find_tool("rc.exe"), None);
+    }
+
+    #[test]
+    fn has_headers() {
+        use std::fs;
+        let root = temp_layout(
+            "find-winsdk-has-headers",
+            &["v8.1/Include/um"],
+            &[
+                "v10/Include/10.0.17763.0/um/windows.h",
+                "v7.1/Include/windows.h",
+            ],
+        );
+        let folder = |name: &str| {
+            root.join(name)
+                .to_str()
+                .expect("invalid temporary folder")
+                .to_owned()
+        };
+        let v10 = synthetic(&folder("v10"), "10.0.17763").has_headers();
+        let v8_1 = synthetic(&folder("v8.1"), "8.1").has_headers();
+        let v7_1 = synthetic(&folder("v7.1"), "7.1").has_headers();
+        fs::remove_dir_all(&root).expect("could not delete test folder");
+        assert!(v10);
+        assert!(!v8_1);
+        assert!(v7_1);
+        assert!(!synthetic(&folder("v10"), "10.0.17763").has_headers());
+    }
+
+    #[test]
+    fn metadata_dirs() {
+        use std::fs;
+        let root = temp_layout(
+            "find-winsdk-metadata-dirs",
+            &["References/10.0.17763.0", "UnionMetadata/10.0.17763.0"],
+            &[],
+        );
+        let folder = root.to_str().expect("invalid temporary folder");
+        let info = synthetic(folder, "10.0.17763");
+        let references = info.winmd_references_dir();
+        let union_metadata = info.union_metadata_dir();
+        let v8_1 = synthetic(folder, "8.1");
+        let v8_1_dirs = (v8_1.winmd_references_dir(), v8_1.union_metadata_dir());
+        fs::remove_dir_all(&root).expect("could not delete test folder");
+        assert_eq!(
+            references,
+            Some(root.join("References").join("10.0.17763.0"))
+        );
+        assert_eq!(
+            union_metadata,
+            Some(root.join("UnionMetadata").join("10.0.17763.0"))
+        );
+        assert_eq!(v8_1_dirs, (None, None));
+        assert_eq!(info.winmd_references_dir(), None);
+        assert_eq!(info.union_metadata_dir(), None);
+    }
+
+    #[test]
+    fn installation_folder_checked() {
+        use std::fs;
+        use std::io;
+        let root = temp_layout("find-winsdk-installation-folder-checked", &[], &[]);
+        let folder = root.to_str().expect("invalid temporary folder").to_owned();
+        let info = synthetic(&folder, "10.0.17763");
+        let present = info.installation_folder_checked().map(PathBuf::from);
+        fs::remove_dir_all(&root).expect("could not delete test folder");
+        assert_eq!(present.expect("existing folder was rejected"), root);
+        let err = info
+            .installation_folder_checked()
+            .expect_err("missing folder was accepted");
+        assert_eq!(err.kind(), io::ErrorKind::NotFound);
+        assert!(err.to_string().contains(&folder));
+    }
+
+    #[test]
+    fn bin_dir() {
+        use std::fs;
+        let root = temp_layout("find-winsdk-bin-dir", &["bin/10.0.17763.0"], &[]);
+        let folder = root.to_str().expect("invalid temporary folder");
+        let versioned = synthetic(folder, "10.0.17763").bin_dir();
+        let unversioned = synthetic(folder, "10.0.19041").bin_dir();
+        let v8_1 = synthetic(folder, "8.1").bin_dir();
+        fs::remove_dir_all(&root).expect("could not delete test folder");
+        assert_eq!(versioned, root.join("bin").join("10.0.17763.0"));
+        assert_eq!(unversioned, root.join("bin"));
+        assert_eq!(v8_1, root.join("bin"));
+    }
+
+    #[test]
+    fn wack_dir_and_appcert_tool() {
+        use std::fs;
+        let root = temp_layout(
+            "find-winsdk-wack",
+            &["without-appcert/App Certification Kit"],
+            &["with-appcert/App Certification Kit/appcert.exe"],
+        );
+        let info = |name: &str| {
+            synthetic(
+                root.join(name).to_str().expect("invalid temporary folder"),
+                "10.0.17763",
+            )
+        };
+        let found = (
+            info("with-appcert").wack_dir(),
+            info("with-appcert").appcert_tool(),
+        );
+        let without_appcert = (
+            info("without-appcert").wack_dir(),
+            info("without-appcert").appcert_tool(),
+        );
+        fs::remove_dir_all(&root).expect("could not delete test folder");
+        let wack = root.join("with-appcert").join("App Certification Kit");
+        assert_eq!(found, (Some(wack.clone()), Some(wack.join("appcert.exe"))));
+        let wack = root.join("without-appcert").join("App Certification Kit");
+        assert_eq!(without_appcert, (Some(wack), None));
+        assert_eq!(info("with-appcert").wack_dir(), None);
+        assert_eq!(info("with-appcert").appcert_tool(), None);
+    }
+
+    #[test]
+    fn product_name_or_default() {
+        use std::borrow::Cow;
+        use Flavor;
+        let info = synthetic(r"C:\find-winsdk", "10.0.19041");
+        assert_eq!(
+            info.product_name_or_default(),
+            "Windows 10 SDK (10.0.19041.0)"
+        );
+        let info = SdkInfo {
+            flavor: Some(Flavor::WithA),
+            ..synthetic(r"C:\find-winsdk", "8.1.51636")
+        };
+        assert_eq!(
+            info.product_name_or_default(),
+            "Windows SDK v8.1A (8.1.51636.0)"
+        );
+        let info = synthetic(r"C:\find-winsdk", "unknown");
+        assert_eq!(info.product_name_or_default(), "Windows SDK (unknown)");
+        let info = SdkInfo {
+            product_name: Some("Windows Software Development Kit".to_owned()),
+            ..info
+        };
+        assert_eq!(
+            info.product_name_or_default(),
+            "Windows Software Development Kit"
+        );
+        match info.display_name() {
+            Cow::Borrowed(name) => assert_eq!(name, "Windows Software Development Kit"),
+            Cow::Owned(name) => panic!("recorded name was copied: {}", name),
+        }
+        let info = synthetic(r"C:\find-winsdk", "10.0.19041");
+        assert_eq!(info.display_name(), "Windows 10 SDK (10.0.19041.0)");
+    }
+
+    #[test]
+    fn is_newer_than() {
+        let v10 = synthetic(r"C:\Windows Kits\10", "10.0.17763");
+        let v8_1 = synthetic(r"C:\Windows Kits\8.1", "8.1");
+        let unknown = synthetic(r"C:\Windows Kits\9.9", "9.9");
+        assert!(v10.is_newer_than(SdkVersion::V8_1));
+        assert!(!v10.is_newer_than(SdkVersion::V10_0));
+        assert!(v10.is_at_least(SdkVersion::V10_0));
+        assert!(!v8_1.is_newer_than(SdkVersion::V8_1));
+        assert!(!v8_1.is_at_least(SdkVersion::V10_0));
+        assert!(v8_1.is_at_least(SdkVersion::V8_1));
+        assert!(v8_1.is_newer_than(SdkVersion::V8_0));
+        assert!(!unknown.is_newer_than(SdkVersion::V6_0));
+        assert!(!unknown.is_at_least(SdkVersion::V6_0));
+        assert!(unknown.is_at_least(SdkVersion::Any));
+    }
+
+    #[test]
+    fn product_version_components() {
+        let components =
+            |version| synthetic(r"C:\Windows Kits\10", version).product_version_components();
+        assert_eq!(components("10.0.19041.0"), [10, 0, 19041, 0]);
+        assert_eq!(components("8.1"), [8, 1]);
+        assert_eq!(components("7.0A"), [7, 0]);
+        assert!(components("7.0AA").is_empty());
+        assert!(components("10.0.x").is_empty());
+        assert!(components("").is_empty());
+    }
+
+    #[test]
+    fn matches_version() {
+        let v10 = synthetic(r"C:\Windows Kits\10", "10.0.17763");
+        let unknown = synthetic(r"C:\Windows Kits\9.9", "9.9");
+        assert!(v10.matches_version(SdkVersion::V10_0));
+        assert!(!v10.matches_version(SdkVersion::V8_1));
+        assert!(v10.matches_version(SdkVersion::Any));
+        assert!(v10.matches_version(SdkVersion::Env));
+        assert!(!unknown.matches_version(SdkVersion::V6_0));
+        assert!(unknown.matches_version(SdkVersion::Any));
+    }
+
+    #[test]
+    fn cmp_version() {
+        use std::cmp::Ordering;
+        let new = synthetic(r"C:\Windows Kits\10", "10.0.19041");
+        let old = synthetic(r"C:\Program Files (x86)\Windows Kits\10", "10.0.17763");
+        let unknown = synthetic(r"C:\Program Files\Microsoft SDKs\Windows\v7.1", "wdf");
+        assert_eq!(new.cmp_version(&old), Ordering::Greater);
+        assert_eq!(old.cmp_version(&new), Ordering::Less);
+        assert_eq!(unknown.cmp_version(&old), Ordering::Less);
+        assert_eq!(unknown.cmp_version(&unknown.clone()), Ordering::Equal);
+        let elsewhere = synthetic(r"D:\Windows Kits\10", "10.0.19041.0");
+        assert_eq!(new.cmp_version(&elsewhere), Ordering::Equal);
+        assert_ne!(new.cmp(&elsewhere), Ordering::Equal);
+    }
+
+    #[test]
+    fn info_ord() {
+        let mut infos = [
+            synthetic(r"C:\Program Files (x86)\Windows Kits\8.1\", "8.1"),
+            synthetic(r"C:\Windows Kits\10\", "10.0.17763"),
+            synthetic(r"C:\Program Files\Microsoft SDKs\Windows\v7.1\", "wdf"),
+            synthetic(r"C:\Program Files (x86)\Windows Kits\10\", "10.0.19041"),
+            synthetic(r"C:\Program Files (x86)\Windows Kits\10\", "10.0.17763"),
+        ];
+        infos.sort_by(|a, b| b.cmp(a));
+        let order: Vec<_> = infos
+            .iter()
+            .map(|i| (i.installation_folder().to_owned(), i.product_version()))
+            .collect();
+        assert_eq!(
+            order,
+            vec![
+                (
+                    PathBuf::from(r"C:\Program Files (x86)\Windows Kits\10\"),
+                    "10.0.19041"
+                ),
+                (PathBuf::from(r"C:\Windows Kits\10\"), "10.0.17763"),
+                (
+                    PathBuf::from(r"C:\Program Files (x86)\Windows Kits\10\"),
+                    "10.0.17763"
+                ),
+                (
+                    PathBuf::from(r"C:\Program Files (x86)\Windows Kits\8.1\"),
+                    "8.1"
+                ),
+                (
+                    PathBuf::from(r"C:\Program Files\Microsoft SDKs\Windows\v7.1\"),
+                    "wdf"
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    #[cfg(target_os = "windows")]
+    fn netfx_tool_path() {
+        use std::env;
+        use std::fs;
+        use std::io;
+        use Arch;
+        let root = env::temp_dir().join("find-winsdk-netfx-tool-path");
+        let old = root.join(r"bin\NETFX 4.0 Tools");
+        let new = root.join(r"bin\NETFX 4.8 Tools");
+        let create = || -> io::Result<()> {
+            fs::create_dir_all(old.join("x64"))?;
+            fs::create_dir_all(new.join("x64"))?;
+            fs::write(old.join(r"x64\sn.exe"), b"")?;
+            fs::write(new.join(r"x64\sn.exe"), b"")?;
+            fs::write(old.join("gacutil.exe"), b"")
+        };
+        let res = create().map(|()| {
+            let info = synthetic(root.to_str().expect("invalid temporary folder"), "8.1");
+            (
+                info.netfx_tool_path("sn", Arch::X64),
+                info.netfx_tool_path("gacutil.exe", Arch::X86),
+                info.netfx_tool_path("gacutil", Arch::X64),
+            )
+        });
+        fs::remove_dir_all(&root).expect("could not delete test folder");
+        let (sn, gacutil, missing) = res.expect("could not create test folder");
+        assert_eq!(sn, Some(new.join(r"x64\sn.exe")));
+        assert_eq!(gacutil, Some(old.join("gacutil.exe")));
+        assert_eq!(missing, None);
+    }
+
+    #[test]
+    fn hash() {
+        use std::collections::hash_map::DefaultHasher;
+        use std::collections::HashSet;
+        use std::hash::{Hash, Hasher};
+        fn hash_of<T: Hash>(value: &T) -> u64 {
+            let mut hasher = DefaultHasher::new();
+            value.hash(&mut hasher);
+            hasher.finish()
+        }
+        let info = synthetic(r"C:\Program Files (x86)\Windows Kits\10\", "10.0.17763");
+        assert_eq!(hash_of(&info), hash_of(&info.clone()));
+        let set: HashSet<_> = vec![info.clone(), info].into_iter().collect();
+        assert_eq!(set.len(), 1);
+        assert_eq!(hash_of(&SdkVersion::V8_1), hash_of(&SdkVersion::V8_1));
+    }
+
+    #[test]
+    fn registry_subkey() {
+        use Flavor;
+        assert_eq!(SdkVersion::Any.registry_subkey(), None);
+        assert_eq!(SdkVersion::Env.registry_subkey(), None);
+        assert_eq!(
+            SdkVersion::V8_1.registry_subkey(),
+            Some(r"SOFTWARE\Microsoft\Microsoft SDKs\Windows\v8.1")
+        );
+        assert_eq!(
+            SdkVersion::V8_1.flavored_registry_subkey(Flavor::WithA),
+            Some(r"SOFTWARE\Microsoft\Microsoft SDKs\Windows\v8.1A")
+        );
+        assert_eq!(
+            SdkVersion::V10_0.flavored_registry_subkey(Flavor::WithA),
+            SdkVersion::V10_0.registry_subkey()
+        );
+        assert!(SdkVersion::Any.registry_keys().is_empty());
+        assert_eq!(
+            SdkVersion::V10_0.registry_keys(),
+            &[r"SOFTWARE\Microsoft\Microsoft SDKs\Windows\v10.0"]
+        );
+        for v in SdkVersion::all_concrete() {
+            let keys = v.registry_keys();
+            assert!(keys.contains(&v.registry_subkey().unwrap()));
+            assert!(keys.contains(&v.flavored_registry_subkey(Flavor::WithA).unwrap()));
+        }
+    }
+
+    #[test]
+    fn find_preferring_build() {
+        use registry::MockRegistry;
+        use std::env;
+        use std::fs;
+        let root = env::temp_dir().join("find-winsdk-find-preferring-build");
+        let res = fs::create_dir_all(root.join("Include").join("10.0.17763.0"));
+        let folder = root.to_str().expect("invalid temporary folder");
+        let _mock = MockRegistry::new()
+            .with_key(
+                r"SOFTWARE\Microsoft\Microsoft SDKs\Windows\v10.0",
+                &[
+                    ("InstallationFolder", folder),
+                    ("ProductVersion", "10.0.19041"),
+                ],
+            )
+            .install();
+        let pinned = SdkInfo::find_preferring_build("10.0.17763.0");
+        let missing = SdkInfo::find_preferring_build("10.0.22000");
+        let any = SdkInfo::find(SdkVersion::Any);
+        fs::remove_dir_all(&root).expect("could not delete test folder");
+        res.expect("could not create test folder");
+        let pinned = pinned
+            .expect("could not read mock registry")
+            .expect("mock SDK was not found");
+        assert_eq!(pinned.product_version(), "10.0.17763");
+        assert_eq!(pinned.installation_folder(), root.as_path());
+        assert_eq!(
+            missing.expect("could not read mock registry"),
+            any.expect("could not read mock registry")
+        );
+    }
+
+    #[test]
+    fn find_any_with_fallback_order() {
+        use registry::MockRegistry;
+        let _mock = MockRegistry::new()
+            .with_key(
+                r"SOFTWARE\Microsoft\Microsoft SDKs\Windows\v10.0",
+                &[
+                    ("InstallationFolder", r"C:\Kits\10\"),
+                    ("ProductVersion", "10.0.19041"),
+                ],
+            )
+            .with_key(
+                r"SOFTWARE\Microsoft\Microsoft SDKs\Windows\v8.1",
+                &[
+                    ("InstallationFolder", r"C:\Kits\8.1\"),
+                    ("ProductVersion", "8.1.25984"),
+                ],
+            )
+            .install();
+        let info = SdkInfo::find_any_with_fallback_order(&[SdkVersion::V8_1, SdkVersion::V10_0])
+            .expect("could not read mock registry")
+            .expect("mock SDK was not found");
+        assert_eq!(info.product_version(), "8.1.25984");
+        assert_eq!(
+            SdkInfo::find_any_with_fallback_order(&[SdkVersion::V7_1])
+                .expect("could not read mock registry"),
+            None
+        );
+    }
+
+    #[test]
+    fn find_v10_build() {
+        use registry::MockRegistry;
+        use std::env;
+        use std::fs;
+        let root = env::temp_dir().join("find-winsdk-find-v10-build");
+        let res = fs::create_dir_all(root.join("Include").join("10.0.18362.0"))
+            .and_then(|()| fs::create_dir_all(root.join("Lib").join("10.0.18362.0")))
+            .and_then(|()| fs::create_dir_all(root.join("Include").join("10.0.19041.0")));
+        let folder = root.to_str().expect("invalid temporary folder");
+        let _mock = MockRegistry::new()
+            .with_key(
+                r"SOFTWARE\Microsoft\Windows Kits\Installed Roots",
+                &[("KitsRoot10", folder)],
+            )
+            .install();
+        let found = SdkInfo::find_v10_build("10.0.18362.0");
+        let headers_only = SdkInfo::find_v10_build("10.0.19041");
+        let missing = SdkInfo::find_v10_build("10.0.22000");
+        let (include, lib) = match found {
+            Ok(Some(ref info)) => (info.include_dir(), info.lib_dir()),
+            _ => Default::default(),
+        };
+        fs::remove_dir_all(&root).expect("could not delete test folder");
+        res.expect("could not create test folder");
+        let info = found
+            .expect("could not read mock registry")
+            .expect("mock SDK build was not found");
+        assert_eq!(info.product_version(), "10.0.18362");
+        assert_eq!(include, root.join("Include").join("10.0.18362.0"));
+        assert_eq!(lib, root.join("Lib").join("10.0.18362.0"));
+        assert_eq!(headers_only.expect("could not read mock registry"), None);
+        assert_eq!(missing.expect("could not read mock registry"), None);
+    }
+
+    #[test]
+    fn mock_find() {
+        use registry::MockRegistry;
+        use {Flavor, FlavorPreference};
+        let _mock = MockRegistry::new()
+            .with_key(
+                r"SOFTWARE\Microsoft\Microsoft SDKs\Windows\v8.1",
+                &[
+                    ("InstallationFolder", r"C:\Kits\8.1\"),
+                    ("ProductVersion", "8.1.25984"),
+                ],
+            )
+            .install();
+        let info = SdkInfo::find(SdkVersion::V8_1)
+            .expect("could not read mock registry")
+            .expect("mock SDK was not found");
+        assert_eq!(info.flavor(), Some(Flavor::Standard));
+        assert!(info.is_at_least(SdkVersion::V8_0));
+        assert_eq!(
+            SdkSearch::new()
+                .version(SdkVersion::V8_1)
+                .find()
+                .expect("could not read mock registry"),
+            Some(info)
+        );
+        assert_eq!(
+            SdkInfo::find_with_flavor(SdkVersion::V8_1, FlavorPreference::OnlyA)
+                .expect("could not read mock registry"),
+            None
+        );
+        assert_eq!(
+            SdkInfo::find(SdkVersion::V10_0).expect("could not read mock registry"),
+            None
+        );
+    }
+
+    #[test]
+    fn version_display() {
+        use SdkVersion::*;
+        assert_eq!(V10_0.to_string(), "v10.0");
+        assert_eq!(V8_1.to_string(), "v8.1");
+        assert_eq!(Any.to_string(), "any");
+        assert_eq!(Env.to_string(), "env");
+    }
+
+    #[test]
+    fn version_serde() {
+        use serde_test::{assert_tokens, Token};
+        use SdkVersion::*;
+        for v in &[Any, Env, V10_0, V8_1, V8_0, V7_1, V7_0, V6_1, V6_0] {
+            assert_tokens(
+                v,
+                &[Token::UnitVariant {
+                    name: "SdkVersion",
+                    variant: v.name(),
+                }],
+            );
+        }
+    }
+
+    #[test]
+    #[cfg(target_os = "windows")]
+    fn normalized_folder() {
+        use serde_test::{assert_de_tokens, Token};
+        use std::path::Path;
+        let expected = synthetic(r"C:\Program Files (x86)\Windows Kits\10", "10.0.17763");
+        for folder in &[
+            r"C:\Program Files (x86)\Windows Kits\10\",
+            r"C:\Program Files (x86)\Windows Kits\10",
+        ] {
+            assert_de_tokens(
+                &expected,
+                &[
+                    Token::Struct {
+                        name: "SdkInfo",
+                        len: 3,
+                    },
+                    Token::Str("InstallationFolder"),
+                    Token::Str(folder),
+                    Token::Str("ProductName"),
+                    Token::None,
+                    Token::Str("ProductVersion"),
+                    Token::Str("10.0.17763"),
+                    Token::StructEnd,
+                ],
+            );
+        }
+        assert_eq!(
+            super::normalize_folder(PathBuf::from(r"C:\")),
+            Path::new(r"C:\")
+        );
+    }
+
+    #[test]
+    fn serialized_field_order() {
+        use serde_test::{assert_ser_tokens, Token};
+        let info = synthetic(r"C:\Program Files (x86)\Windows Kits\10", "10.0.17763");
+        assert_ser_tokens(
+            &info,
+            &[
+                Token::Struct {
+                    name: "SdkInfo",
+                    len: 7,
+                },
+                Token::Str("InstallationFolder"),
+                Token::Str(r"C:\Program Files (x86)\Windows Kits\10"),
+                Token::Str("ProductName"),
+                Token::None,
+                Token::Str("ProductVersion"),
+                Token::Str("10.0.17763"),
+                Token::Str("BinDirFromEnv"),
+                Token::None,
+                Token::Str("Flavor"),
+                Token::None,
+                Token::Str("RawValues"),
+                Token::Map { len: Some(0) },
+                Token::MapEnd,
+                Token::Str("RegistryLastWrite"),
+                Token::None,
+                Token::StructEnd,
+            ],
+        );
+    }
+
+    #[test]
+    fn install_root_drive() {
+        let drive = |folder| synthetic(folder, "10.0.17763").install_root_drive();
+        assert_eq!(
+            drive(r"C:\Program Files (x86)\Windows Kits\10"),
+            Some("C:".to_owned())
+        );
+        assert_eq!(drive("d:/Windows Kits/10"), Some("D:".to_owned()));
+        assert_eq!(
+            drive(r"\\server\share\Windows Kits\10"),
+            Some(r"\\server\share".to_owned())
+        );
+        assert_eq!(
+            drive(r"\\?\C:\Program Files (x86)\Windows Kits\10"),
+            Some("C:".to_owned())
+        );
+        assert_eq!(
+            drive(r"\\?\UNC\server\share\Windows Kits\10"),
+            Some(r"\\server\share".to_owned())
+        );
+        assert_eq!(drive(r"Windows Kits\10"), None);
+        assert_eq!(drive(r"\Windows Kits\10"), None);
+        assert_eq!(drive(r"C:Windows Kits"), None);
+        assert_eq!(drive(r"\\server"), None);
+        assert_eq!(drive(""), None);
+    }
+
+    #[test]
+    fn platform_props_dir() {
+        use std::env;
+        use std::fs;
+        let root = env::temp_dir().join("find-winsdk-platform-props-dir");
+        let neutral = root
+            .join("DesignTime")
+            .join("CommonConfiguration")
+            .join("Neutral");
+        let res = fs::create_dir_all(&neutral);
+        let folder = root.to_str().expect("invalid temporary folder");
+        let found = synthetic(folder, "10.0.17763").platform_props_dir();
+        fs::remove_dir_all(&root).expect("could not delete test folder");
+        res.expect("could not create test folder");
+        assert_eq!(found, Some(neutral));
+        assert_eq!(synthetic(folder, "10.0.17763").platform_props_dir(), None);
+    }
+
+    #[test]
+    fn platform_props_dir_build() {
+        use std::env;
+        use std::fs;
+        let root = env::temp_dir().join("find-winsdk-platform-props-dir-build");
+        let versioned = root.join("build").join("10.0.17763.0");
+        let res = fs::create_dir_all(&versioned).and_then(|()| {
+            fs::create_dir_all(
+                root.join("DesignTime")
+                    .join("CommonConfiguration")
+                    .join("Neutral"),
+            )
+        });
+        let folder = root.to_str().expect("invalid temporary folder");
+        let found = synthetic(folder, "10.0.17763").platform_props_dir();
+        let unversioned = synthetic(folder, "10.0.19041").platform_props_dir();
+        fs::remove_dir_all(&root).expect("could not delete test folder");
+        res.expect("could not create test folder");
+        assert_eq!(found, Some(versioned));
+        assert_eq!(unversioned, Some(root.join("build")));
+    }
+
+    #[test]
+    fn summary() {
+        let info = synthetic(r"C:\Program Files (x86)\Windows Kits\10", "10.0.19041");
+        assert_eq!(
+            info.summary(),
+            r"Windows 10 SDK 10.0.19041.0 at C:\Program Files (x86)\Windows Kits\10"
+        );
+        let named = SdkInfo {
+            product_name: Some("Windows Software Development Kit for Windows 8.1".to_owned()),
+            ..synthetic(r"C:\Program Files (x86)\Windows Kits\8.1", "8.1.25984")
+        };
+        assert_eq!(
+            named.summary(),
+            r"Windows Software Development Kit for Windows 8.1 8.1.25984.0 at C:\Program Files (x86)\Windows Kits\8.1"
+        );
+    }
+
+    #[test]
+    fn validate() {
+        use std::env;
+        use std::fs;
+        use std::io;
+        let root = env::temp_dir().join("find-winsdk-validate");
+        let res = fs::create_dir_all(&root);
+        let folder = root.to_str().expect("invalid temporary folder");
+        let kind = |info: SdkInfo| info.validate().err().map(|e| e.kind());
+        let no_include = kind(synthetic(folder, "10.0.17763"));
+        let res = res.and_then(|()| fs::create_dir_all(root.join("Include")));
+        let valid = kind(synthetic(folder, "10.0.17763"));
+        let invalid_version = kind(synthetic(folder, "wdf"));
+        fs::remove_dir_all(&root).expect("could not delete test folder");
+        res.expect("could not create test folder");
+        assert_eq!(no_include, Some(io::ErrorKind::NotFound));
+        assert_eq!(valid, None);
+        assert_eq!(invalid_version, Some(io::ErrorKind::InvalidData));
+        assert_eq!(
+            kind(synthetic(folder, "10.0.17763")),
+            Some(io::ErrorKind::NotFound)
+        );
+    }
+
+    #[test]
+    fn into_path_buf() {
+        use std::path::PathBuf;
+        let info = synthetic(r"C:\Windows Kits\10", "10.0.17763");
+        let borrowed: PathBuf = (&info).into();
+        assert_eq!(borrowed, info.installation_folder());
+        let owned: PathBuf = info.into();
+        assert_eq!(owned, borrowed);
+    }
+
+    #[test]
+    fn diff() {
+        use FieldDiff;
+        let old = synthetic(r"C:\Program Files (x86)\Windows Kits\10\", "10.0.17763");
+        let same = synthetic(r"c:\program files (x86)\windows kits\10", "10.0.17763");
+        assert_eq!(old.diff(&same), []);
+        let new = SdkInfo {
+            product_name: Some("Windows 10 SDK".to_owned()),
+            ..synthetic(r"D:\Windows Kits\10", "10.0.19041")
+        };
+        assert_eq!(
+            old.diff(&new),
+            [
+                FieldDiff::InstallationFolder,
+                FieldDiff::ProductName,
+                FieldDiff::ProductVersion
+            ]
+        );
+    }
+
+    #[test]
+    fn ordering_key() {
+        let mut infos = [
+            synthetic(r"C:\a", "10.0.19041.0"),
+            synthetic(r"C:\b", "8.1"),
+            synthetic(r"C:\c", "wdf"),
+            synthetic(r"C:\d", "10.0.9600"),
+        ];
+        infos.sort_by_key(SdkInfo::ordering_key);
+        let versions: Vec<_> = infos.iter().map(SdkInfo::product_version).collect();
+        assert_eq!(versions, ["wdf", "8.1", "10.0.9600", "10.0.19041.0"]);
+        assert_eq!(infos[3].ordering_key(), (10, 0, 19041, 0));
+        assert_eq!(infos[0].ordering_key(), (0, 0, 0, 0));
+    }
+
+    #[test]
+    fn version_from_numeric() {
+        use SdkVersion::*;
+        let from = |s: &str| SdkVersion::from_version(&s.parse().expect("invalid version"));
+        assert_eq!(from("10.0.19041.0"), Some(V10_0));
+        assert_eq!(from("10.0"), Some(V10_0));
+        assert_eq!(from("8.1.25984"), Some(V8_1));
+        assert_eq!(from("8.1A"), Some(V8_1));
+        assert_eq!(from("8.0"), Some(V8_0));
+        assert_eq!(from("6.0"), Some(V6_0));
+        assert_eq!(from("8.2"), None);
+        assert_eq!(from("10.1"), None);
+        assert_eq!(from("11.0"), None);
+        assert_eq!(from("0.0"), None);
+    }
+
+    #[test]
+    fn version_from_str() {
+        use SdkVersion::*;
+        for v in &[Any, Env, V10_0, V8_1, V8_0, V7_1, V7_0, V6_1, V6_0] {
+            assert_eq!(v.to_string().parse(), Ok(*v));
+        }
+        assert_eq!("V10.0".parse(), Ok(V10_0));
+        assert_eq!("8.1".parse(), Ok(V8_1));
+        assert_eq!("ANY".parse(), Ok(Any));
+        assert!("vany".parse::<SdkVersion>().is_err());
+        assert!("v9.0".parse::<SdkVersion>().is_err());
+    }
+
+    #[test]
+    fn arch_strings() {
+        use Arch;
+        assert_eq!(Arch::all().len(), 4);
+        for arch in Arch::all() {
+            assert_eq!(arch.to_string().parse(), Ok(*arch));
+        }
+        assert_eq!("X64".parse(), Ok(Arch::X64));
+        assert_eq!(Arch::Arm64.to_string(), "arm64");
+        assert!("amd64".parse::<Arch>().is_err());
+    }
+
+    #[test]
+    #[cfg(target_arch = "x86_64")]
+    fn arch_host() {
+        use Arch;
+        assert_eq!(Arch::host(), Some(Arch::X64));
+    }
+
+    #[test]
+    fn find_where() {
+        let all = SdkInfo::find_all().expect("could not retrieve Windows SDK info from registry");
+        let first = SdkInfo::find_where(|_| true)
+            .expect("could not retrieve Windows SDK info from registry");
+        assert_eq!(all.first(), first.as_ref());
+        let none = SdkInfo::find_where(|_| false)
+            .expect("could not retrieve Windows SDK info from registry");
+        assert_eq!(none, None);
+    }
+
+    #[test]
+    fn at_least() {
+        let info = SdkInfo::find_at_least(SdkVersion::V8_0)
+            .expect("could not retrieve Windows SDK info from registry");
+        if let Some(info) = info {
+            let release = info.version().and_then(|v| SdkVersion::from_version(&v));
+            assert!(release >= Some(SdkVersion::V8_0));
+        }
+    }
+
+    #[test]
+    #[cfg(target_os = "windows")]
+    fn winsdk_env() {
+        let _ = SdkInfo::find(SdkVersion::Env)
+            .expect("environment specifies an invalid Windows SDK installation")
+            .expect("environment does not specify a Windows SDK installation");
+    }
+
+    #[test]
+    fn version_ord() {
+        use SdkVersion::*;
+        assert!(V10_0 > V8_1);
+        assert!(Env > Any);
+        assert!(V6_0 > Env);
+        let mut vers = vec![V8_1, Any, V6_0, V10_0, V7_1, Env, V6_1, V8_0, V7_0];
+        vers.sort();
+        assert_eq!(vers, [Any, Env, V6_0, V6_1, V7_0, V7_1, V8_0, V8_1, V10_0]);
+    }
+
+    #[test]
+    #[cfg(target_os = "windows")]
+    fn winsdk_10_0() {
+        let _ = SdkInfo::find(SdkVersion::V10_0)
+            .expect("could not retrieve Windows 10 SDK info from registry")
+            .expect("Windows 10 SDK is not installed");
+    }
+
+    #[test]
+    #[cfg(target_os = "windows")]
+    fn winsdk_10_0_build_numbers() {
+        let info = SdkInfo::find(SdkVersion::V10_0)
+            .expect("could not retrieve Windows 10 SDK info from registry")
+            .expect("Windows 10 SDK is not installed");
+        let builds = info
+            .build_numbers()
+            .expect("could not read Windows 10 SDK include folder");
+        assert!(!builds.is_empty());
+        let latest = info
+            .latest_build_dir()
+            .expect("could not read Windows 10 SDK include folder");
+        assert_eq!(latest.as_ref(), builds.first());
+    }
+
+    #[test]
+    fn latest_build_dir() {
+        use std::env;
+        use std::fs;
+        use std::io;
+        let root = env::temp_dir().join("find-winsdk-latest-build-dir");
+        let create = || -> io::Result<()> {
+            for name in &["10.0.9600.0", "10.0.17763.0", "10.0.10240.0", "wdf"] {
+                fs::create_dir_all(root.join("Include").join(name))?;
+            }
+            Ok(())
+        };
+        let res = create().and_then(|()| {
+            let folder = root.to_str().expect("invalid temporary folder");
+            synthetic(folder, "10.0.10240").latest_build_dir()
+        });
+        fs::remove_dir_all(&root).expect("could not delete test folder");
+        assert_eq!(
+            res.expect("could not read test folder"),
+            Some("10.0.17763.0".to_owned())
+        );
+    }
+
+    #[test]
+    #[cfg(target_os = "windows")]
+    fn winsdk_10_0_find_all_v10() {
+        let all = SdkSearch::new()
+            .include_preview(true)
+            .find_all_v10()
+            .expect("could not retrieve Windows 10 SDK info");
+        assert!(
+            SdkInfo::find_all_v10()
+                .expect("could not retrieve Windows 10 SDK info")
+                .len()
+                <= all.len()
+        );
+        let info = SdkInfo::find(SdkVersion::V10_0)
+            .expect("could not retrieve Windows 10 SDK info from registry")
+            .expect("Windows 10 SDK is not installed");
+        assert!(!all.is_empty());
+        for (build, name) in all.iter().zip(info.build_numbers().unwrap_or_default()) {
+            assert_eq!(build.installation_folder(), info.installation_folder());
+            assert_eq!(
+                build.include_dir(),
+                info.installation_folder().join("Include").join(name)
+            );
+        }
+    }
+
+    #[test]
+    #[cfg(target_os = "windows")]
+    fn winsdk_10_0_registered_builds() {
+        let info = SdkInfo::find(SdkVersion::V10_0)
+            .expect("could not retrieve Windows 10 SDK info from registry")
+            .expect("Windows 10 SDK is not installed");
+        let builds = info
+            .registered_builds()
+            .expect("could not retrieve Windows 10 SDK builds from registry");
+        assert!(!builds.is_empty());
+    }
+
+    #[test]
+    #[cfg(target_os = "windows")]
+    fn winsdk_10_0_include_dir() {
+        let info = SdkInfo::find(SdkVersion::V10_0)
+            .expect("could not retrieve Windows 10 SDK info from registry")
+            .expect("Windows 10 SDK is not installed");
+        assert!(info.include_dir().is_dir());
+    }
+
+    #[test]
+    #[cfg(target_os = "windows")]
+    fn winsdk_10_0_tool_path() {
+        use Arch;
+        let info = SdkInfo::find(SdkVersion::V10_0)
+            .expect("could not retrieve Windows 10 SDK info from registry")
+            .expect("Windows 10 SDK is not installed");
+        let rc = info
+            .tool_path("rc", Arch::X64)
+            .expect("could not find rc.exe for x64");
+        assert_eq!(rc.file_name().and_then(|s| s.to_str()), Some("rc.exe"));
+        assert_eq!(info.tool_path("not-a-real-tool", Arch::X64), None);
+        assert!(info.exists_tool("signtool"));
+        assert!(!info.exists_tool("not-a-real-tool"));
+        let (arch, signtool) = info
+            .find_tool("signtool")
+            .expect("could not find signtool.exe for the host");
+        assert_eq!(info.tool_path("signtool", arch), Some(signtool.clone()));
+        let version = info
+            .tool_file_version(&signtool)
+            .expect("could not read signtool.exe")
+            .expect("signtool.exe has no version-info resource");
+        assert_eq!(version.major, 10);
+        assert!(info
+            .tool_file_version(&info.installation_folder().join("does-not-exist.exe"))
+            .is_err());
+        assert_eq!(info.find_tool("not-a-real-tool"), None);
+    }
+
+    #[test]
+    #[cfg(target_os = "windows")]
+    fn winsdk_10_0_ucrt() {
+        use Arch;
+        let info = SdkInfo::find(SdkVersion::V10_0)
+            .expect("could not retrieve Windows 10 SDK info from registry")
+            .expect("Windows 10 SDK is not installed");
+        assert!(info.ucrt_include_dir().is_some());
+        assert!(info.ucrt_lib_dir(Arch::X64).is_some());
+    }
+
+    #[test]
+    #[cfg(target_os = "windows")]
+    fn winsdk_10_0_um_shared() {
+        use Arch;
+        let info = SdkInfo::find(SdkVersion::V10_0)
+            .expect("could not retrieve Windows 10 SDK info from registry")
+            .expect("Windows 10 SDK is not installed");
+        assert!(info.um_include_dir().is_some());
+        assert_eq!(
+            info.installation_folder_checked().ok(),
+            Some(info.installation_folder())
+        );
+        assert!(info.has_headers());
+        assert!(info.shared_include_dir().is_some());
+        assert!(info.winrt_include_dir().is_some());
+        assert!(info.winmd_references_dir().is_some());
+        assert!(info.union_metadata_dir().is_some());
+        assert!(info.um_lib_dir(Arch::X64).is_some());
+    }
+
+    #[test]
+    #[cfg(target_os = "windows")]
+    fn winsdk_10_0_cppwinrt() {
+        use Arch;
+        let info = SdkInfo::find(SdkVersion::V10_0)
+            .expect("could not retrieve Windows 10 SDK info from registry")
+            .expect("Windows 10 SDK is not installed");
+        if let Some(dir) = info.cppwinrt_include_dir() {
+            assert_eq!(dir, info.include_dir().join("cppwinrt"));
+        }
+        if let Some(tool) = info.cppwinrt_tool(Arch::X64) {
+            assert_eq!(tool.file_name(), Some("cppwinrt.exe".as_ref()));
+        }
+    }
+
+    #[test]
+    fn relative_tool_paths() {
+        use std::env;
+        use std::fs;
+        use std::io;
+        use std::path::PathBuf;
+        let root = env::temp_dir().join("find-winsdk-relative-tool-paths");
+        let build = root.join("bin").join("10.0.17763.0");
+        let create = || -> io::Result<()> {
+            fs::create_dir_all(build.join("x64"))?;
+            fs::create_dir_all(build.join("x86"))?;
+            fs::write(build.join("x64").join("signtool.exe"), b"")?;
+            fs::write(build.join("x86").join("signtool.exe"), b"")?;
+            fs::write(build.join("x64").join("signtool.exe.manifest"), b"")?;
+            fs::write(root.join("bin").join("makecat.EXE"), b"")
+        };
+        let res = create().and_then(|()| {
+            let folder = root.to_str().expect("invalid temporary folder");
+            synthetic(folder, "10.0.17763").relative_tool_paths()
+        });
+        fs::remove_dir_all(&root).expect("could not delete test folder");
+        let bin = PathBuf::from("bin");
+        let build = bin.join("10.0.17763.0");
+        assert_eq!(
+            res.expect("could not read test folder"),
+            [
+                build.join("x64").join("signtool.exe"),
+                build.join("x86").join("signtool.exe"),
+                bin.join("makecat.EXE"),
+            ]
+        );
+    }
+
+    #[test]
+    fn contains_header() {
+        use std::env;
+        use std::fs;
+        use std::io;
+        let root = env::temp_dir().join("find-winsdk-contains-header");
+        let include = root.join("Include");
+        let create = || -> io::Result<()> {
+            fs::create_dir_all(include.join("um"))?;
+            fs::create_dir_all(include.join("cppwinrt").join("winrt"))?;
+            fs::write(include.join("um").join("d3d12.h"), b"")?;
+            fs::write(include.join("cppwinrt").join("winrt").join("base.h"), b"")
+        };
+        let res = create().map(|()| {
+            let folder = root.to_str().expect("invalid temporary folder");
+            let v10 = synthetic(folder, "10.0.17763");
+            let v8_1 = synthetic(folder, "8.1");
+            (
+                v10.contains_header("d3d12.h"),
+                v10.contains_header("winrt/base.h"),
+                v10.contains_header("windows.h"),
+                v8_1.contains_header("winrt/base.h"),
+            )
+        });
+        fs::remove_dir_all(&root).expect("could not delete test folder");
+        let found = res.expect("could not create test folder");
+        assert_eq!(found, (true, true, false, false));
+    }
+
+    #[test]
+    fn shader_compiler() {
+        use std::env;
+        use std::fs;
+        use {Arch, ShaderCompiler};
+        let root = env::temp_dir().join("find-winsdk-shader-compiler");
+        let bin = root.join("bin").join("x64");
+        let res = fs::create_dir_all(&bin).and_then(|()| fs::write(bin.join("fxc.exe"), b""));
+        let info = synthetic(root.to_str().expect("invalid temporary folder"), "8.1");
+        let fxc = info.shader_compiler(ShaderCompiler::Fxc, Arch::X64);
+        let dxc = info.shader_compiler(ShaderCompiler::Dxc, Arch::X64);
+        fs::remove_dir_all(&root).expect("could not delete test folder");
+        res.expect("could not create test folder");
+        assert_eq!(fxc, Some(bin.join("fxc.exe")));
+        assert_eq!(dxc, None);
+    }
+
+    #[test]
+    fn cargo_directives() {
+        use std::env;
+        use std::fs;
+        use Arch;
+        let root = env::temp_dir().join("find-winsdk-cargo-directives");
+        let um = root.join("Lib").join("um").join("x64");
+        let ucrt = root.join("Lib").join("ucrt").join("x64");
+        let res = fs::create_dir_all(&um).and_then(|()| fs::create_dir_all(&ucrt));
+        let folder = root.to_str().expect("invalid temporary folder");
+        let directives = synthetic(folder, "10.0.17763").cargo_directives(Arch::X64);
+        let missing = synthetic(folder, "10.0.17763").cargo_directives(Arch::Arm64);
+        fs::remove_dir_all(&root).expect("could not delete test folder");
+        res.expect("could not create test folder");
+        assert_eq!(
+            directives,
+            [
+                format!("cargo:rustc-link-search=native={}", um.display()),
+                format!("cargo:rustc-link-search=native={}", ucrt.display()),
+            ]
+        );
+        assert!(missing.is_empty());
+    }
+
+    #[test]
+    #[cfg(target_os = "windows")]
+    fn winsdk_10_0_environment_vars() {
+        use Arch;
+        let info = SdkInfo::find(SdkVersion::V10_0)
+            .expect("could not retrieve Windows 10 SDK info from registry")
+            .expect("Windows 10 SDK is not installed");
+        let vars = info.environment_vars(Arch::X64);
+        let um = info
+            .um_include_dir()
+            .expect("Windows 10 SDK has no um headers");
+        assert!(vars["INCLUDE"]
+            .split(';')
+            .any(|dir| um.to_str() == Some(dir)));
+        assert!(vars.contains_key("LIB"));
+        assert!(vars.contains_key("PATH"));
+        let missing = synthetic(r"C:\find-winsdk\does\not\exist", "10.0.17763");
+        assert!(missing.environment_vars(Arch::X64).is_empty());
+    }
+
+    #[test]
+    #[cfg(target_os = "windows")]
+    fn winsdk_10_0_supports_arch() {
+        use Arch;
+        let info = SdkInfo::find(SdkVersion::V10_0)
+            .expect("could not retrieve Windows 10 SDK info from registry")
+            .expect("Windows 10 SDK is not installed");
+        for arch in Arch::all() {
+            assert_eq!(
+                info.supports_arch(*arch),
+                info.um_lib_dir(*arch).is_some() && info.ucrt_lib_dir(*arch).is_some()
+            );
+        }
+        assert!(info.supports_arch(Arch::X64));
+    }
+
+    #[test]
+    #[cfg(target_os = "windows")]
+    fn winsdk_10_0_lib_dir() {
+        let info = SdkInfo::find(SdkVersion::V10_0)
+            .expect("could not retrieve Windows 10 SDK info from registry")
+            .expect("Windows 10 SDK is not installed");
+        assert!(info.lib_dir().is_dir());
+        assert!(info.bin_dir().is_dir());
+    }
+
+    #[test]
+    #[cfg(target_os = "windows")]
+    fn winsdk_8_1() {
+        let info = SdkInfo::find(SdkVersion::V8_1)
+            .expect("could not retrieve Windows 8.1 SDK info from registry")
+            .expect("Windows 8.1 SDK is not installed");
+        assert!(info.flavor().is_some());
+    }
+
+    #[test]
+    #[cfg(target_os = "windows")]
+    fn winsdk_8_1_standard() {
+        use Flavor;
+        let info = SdkSearch::new()
+            .version(SdkVersion::V8_1)
+            .prefer_flavor(Flavor::Standard)
+            .find()
+            .expect("could not retrieve Windows 8.1 SDK info from registry")
+            .expect("Windows 8.1 SDK is not installed");
+        let other = SdkInfo::find(SdkVersion::V8_1)
+            .expect("could not retrieve Windows 8.1 SDK info from registry")
+            .expect("Windows 8.1 SDK is not installed");
+        // Only one flavour might be installed, but the preferred one wins if both are.
+        if info != other {
+            assert_eq!(info.flavor(), Some(Flavor::Standard));
+            assert_eq!(other.flavor(), Some(Flavor::WithA));
+        }
+    }
+
+    #[test]
+    #[cfg(target_os = "windows")]
+    fn winsdk_8_1_only_flavor() {
+        use {Flavor, FlavorPreference};
+        let only_a = SdkInfo::find_with_flavor(SdkVersion::V8_1, FlavorPreference::OnlyA)
+            .expect("could not retrieve Windows 8.1 SDK info from registry");
+        let only_standard =
+            SdkInfo::find_with_flavor(SdkVersion::V8_1, FlavorPreference::OnlyStandard)
+                .expect("could not retrieve Windows 8.1 SDK info from registry");
+        assert!(only_a.is_some() || only_standard.is_some());
+        if let Some(info) = only_a {
+            assert_eq!(info.flavor(), Some(Flavor::WithA));
+        }
+        if let Some(info) = only_standard {
+            assert_eq!(info.flavor(), Some(Flavor::Standard));
+        }
     }
 }
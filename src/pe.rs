@@ -0,0 +1,162 @@
+// Copyright (c) 2018 FaultyRAM
+//
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or http://www.apache.org/licenses/LICENSE-2.0> or the
+// MIT license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at
+// your option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Reading the file version of executables from their version-info resource.
+
+use std::fs;
+use std::io;
+use std::path::Path;
+use Version;
+
+/// The signature that starts a `VS_FIXEDFILEINFO` structure, in little-endian byte order.
+const FIXED_FILE_INFO_SIGNATURE: [u8; 4] = [0xbd, 0x04, 0xef, 0xfe];
+
+/// The name of the section that holds an image's resources.
+const RESOURCE_SECTION_NAME: &[u8; 8] = b".rsrc\0\0\0";
+
+/// The size of a section header in the section table.
+const SECTION_HEADER_SIZE: usize = 40;
+
+/// Returns the file version of an executable or DLL, if it has a version-info resource.
+pub fn file_version(path: &Path) -> io::Result<Option<Version>> {
+    fs::read(path).map(|data| image_file_version(&data))
+}
+
+/// Returns the file version from the version-info resource of a PE image, if it has one.
+///
+/// Only the `.rsrc` section is searched, since other sections, e.g. code that checks for it, can
+/// contain the `VS_FIXEDFILEINFO` signature too.
+fn image_file_version(data: &[u8]) -> Option<Version> {
+    resource_section(data).and_then(fixed_file_version)
+}
+
+/// Returns the raw data of the `.rsrc` section of a PE image, as found through its section table.
+fn resource_section(data: &[u8]) -> Option<&[u8]> {
+    let bytes_at = |offset: usize, len: usize| data.get(offset..)?.get(..len);
+    let le_at = |offset: usize, len: usize| {
+        bytes_at(offset, len).map(|bytes| {
+            bytes
+                .iter()
+                .rev()
+                .fold(0, |value, byte| value << 8 | usize::from(*byte))
+        })
+    };
+    let u16_at = |offset: usize| le_at(offset, 2);
+    let u32_at = |offset: usize| le_at(offset, 4);
+    if bytes_at(0, 2) != Some(b"MZ") {
+        return None;
+    }
+    let pe = u32_at(0x3c)?;
+    if bytes_at(pe, 4) != Some(b"PE\0\0") {
+        return None;
+    }
+    let coff = pe + 4;
+    let sections = coff + 20 + u16_at(coff + 16)?;
+    (0..u16_at(coff + 2)?)
+        .map(|i| sections + i * SECTION_HEADER_SIZE)
+        .find(|header| bytes_at(*header, 8) == Some(&RESOURCE_SECTION_NAME[..]))
+        .and_then(|header| bytes_at(u32_at(header + 20)?, u32_at(header + 16)?))
+}
+
+/// Returns the file version from the first `VS_FIXEDFILEINFO` structure in `data`, the contents
+/// of a resource section.
+///
+/// The structure is always aligned to four bytes within the resource, which is itself aligned
+/// within the section, so only aligned offsets are checked. The signature is followed by the
+/// structure version, then the most and least significant halves of the file version.
+fn fixed_file_version(data: &[u8]) -> Option<Version> {
+    data.chunks(4)
+        .position(|chunk| chunk == FIXED_FILE_INFO_SIGNATURE)
+        .and_then(|i| data.get(i * 4 + 8..i * 4 + 16))
+        .map(|version| {
+            let word =
+                |offset: usize| u32::from(version[offset]) | u32::from(version[offset + 1]) << 8;
+            Version::new(word(2), word(0), word(6), word(4))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{fixed_file_version, image_file_version};
+    use std::convert::TryFrom;
+    use Version;
+
+    /// Returns a minimal PE image with the given sections, as pairs of names and raw data.
+    fn image(sections: &[(&[u8; 8], &[u8])]) -> Vec<u8> {
+        let mut data = vec![0; 0x40];
+        data[..2].copy_from_slice(b"MZ");
+        data[0x3c] = 0x40;
+        data.extend_from_slice(b"PE\0\0");
+        let mut coff = [0; 20];
+        coff[2] = u8::try_from(sections.len()).expect("too many sections");
+        data.extend_from_slice(&coff);
+        let mut offset = data.len() + sections.len() * 40;
+        let le = |value: usize| u32::try_from(value).expect("image too large").to_le_bytes();
+        for &(name, raw) in sections {
+            let mut header = [0; 40];
+            header[..8].copy_from_slice(name);
+            header[16..20].copy_from_slice(&le(raw.len()));
+            header[20..24].copy_from_slice(&le(offset));
+            data.extend_from_slice(&header);
+            offset += raw.len();
+        }
+        for &(_, raw) in sections {
+            data.extend_from_slice(raw);
+        }
+        data
+    }
+
+    /// Returns a `VS_FIXEDFILEINFO` structure's signature and version fields.
+    fn fixed_file_info_bytes(version: [u16; 4]) -> Vec<u8> {
+        let mut data = vec![0xbd, 0x04, 0xef, 0xfe, 0x00, 0x00, 0x01, 0x00];
+        for word in &[version[1], version[0], version[3], version[2]] {
+            data.extend_from_slice(&word.to_le_bytes());
+        }
+        data
+    }
+
+    #[test]
+    fn resource_section_only() {
+        let text = fixed_file_info_bytes([1, 2, 3, 4]);
+        let rsrc = fixed_file_info_bytes([10, 0, 19041, 0]);
+        let data = image(&[(b".text\0\0\0", &text), (b".rsrc\0\0\0", &rsrc)]);
+        assert_eq!(
+            image_file_version(&data),
+            Some(Version::new(10, 0, 19041, 0))
+        );
+        let data = image(&[(b".text\0\0\0", &text)]);
+        assert_eq!(image_file_version(&data), None);
+        assert_eq!(image_file_version(&rsrc), None);
+    }
+
+    #[test]
+    fn fixed_file_info() {
+        let mut data = vec![0; 12];
+        data.extend_from_slice(&[0xbd, 0x04, 0xef, 0xfe, 0x00, 0x00, 0x01, 0x00]);
+        data.extend_from_slice(&[0x00, 0x00, 0x0a, 0x00, 0x00, 0x00, 0x61, 0x4a]);
+        assert_eq!(
+            fixed_file_version(&data),
+            Some(Version::new(10, 0, 19041, 0))
+        );
+    }
+
+    #[test]
+    fn no_fixed_file_info() {
+        assert_eq!(fixed_file_version(b"MZ"), None);
+        // Unaligned signatures are not part of a version-info resource.
+        let data = [
+            0, 0xbd, 0x04, 0xef, 0xfe, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        ];
+        assert_eq!(fixed_file_version(&data), None);
+        // Truncated structures are ignored.
+        assert_eq!(
+            fixed_file_version(&[0xbd, 0x04, 0xef, 0xfe, 0, 0, 1, 0]),
+            None
+        );
+    }
+}
@@ -0,0 +1,257 @@
+// Copyright (c) 2018 FaultyRAM
+//
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or http://www.apache.org/licenses/LICENSE-2.0> or the
+// MIT license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at
+// your option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Detection of Windows SDK installations at their default locations, without the registry.
+
+use registry::{self, RegistryHive, RegistryView};
+use search::{is_preview_build, trim_zero_revision};
+use std::collections::BTreeMap;
+use std::env;
+use std::path::{Path, PathBuf};
+use {normalize_folder, FindSdkError, Flavor, SdkInfo, SdkVersion, Version};
+
+/// Returns installation information for a Windows SDK installed at its default location.
+///
+/// For releases that come in two flavours, `flavor` is probed first, then the other flavour.
+/// Preview builds of the Windows 10 SDK are only returned if `include_preview` is set.
+pub fn find(version: SdkVersion, flavor: Flavor, include_preview: bool) -> Option<SdkInfo> {
+    if version == SdkVersion::V10_0 {
+        return find_v10(include_preview);
+    }
+    let (with_a, standard, product_version) = double_release_folders(version)?;
+    let candidates = match flavor {
+        Flavor::WithA => [(with_a, Flavor::WithA), (standard, Flavor::Standard)],
+        Flavor::Standard => [(standard, Flavor::Standard), (with_a, Flavor::WithA)],
+    };
+    candidates
+        .iter()
+        .find(|candidate| {
+            let found = candidate.0.join("Include").is_dir();
+            trace!("probed `{}`, found: {}", candidate.0.display(), found);
+            found
+        })
+        .map(|candidate| SdkInfo {
+            installation_folder: candidate.0.clone(),
+            product_name: None,
+            product_version: product_version.to_owned(),
+            bin_dir_from_env: None,
+            flavor: Some(candidate.1),
+            raw_values: BTreeMap::new(),
+            registry_last_write: None,
+        })
+}
+
+/// Returns the default installation folders of a release, in the order that `find` probes them.
+pub fn default_folders(version: SdkVersion, flavor: Flavor) -> Vec<PathBuf> {
+    if version == SdkVersion::V10_0 {
+        return vec![v10_folder()];
+    }
+    match double_release_folders(version) {
+        Some((with_a, standard, _)) => match flavor {
+            Flavor::WithA => vec![with_a, standard],
+            Flavor::Standard => vec![standard, with_a],
+        },
+        None => Vec::new(),
+    }
+}
+
+/// Returns the default installation folders of the `A` and standard flavours of a release, along
+/// with its product version.
+fn double_release_folders(version: SdkVersion) -> Option<(PathBuf, PathBuf, &'static str)> {
+    match version {
+        SdkVersion::Any | SdkVersion::Env | SdkVersion::V10_0 => None,
+        SdkVersion::V8_1 => Some((
+            program_files_x86().join(r"Microsoft SDKs\Windows\v8.1A"),
+            kits_folder("KitsRoot81", program_files_x86().join(r"Windows Kits\8.1")),
+            "8.1",
+        )),
+        SdkVersion::V8_0 => Some((
+            program_files_x86().join(r"Microsoft SDKs\Windows\v8.0A"),
+            kits_folder("KitsRoot", program_files_x86().join(r"Windows Kits\8.0")),
+            "8.0",
+        )),
+        SdkVersion::V7_1 => Some((
+            program_files_x86().join(r"Microsoft SDKs\Windows\v7.1A"),
+            program_files().join(r"Microsoft SDKs\Windows\v7.1"),
+            "7.1",
+        )),
+        SdkVersion::V7_0 => Some((
+            program_files_x86().join(r"Microsoft SDKs\Windows\v7.0A"),
+            program_files().join(r"Microsoft SDKs\Windows\v7.0"),
+            "7.0",
+        )),
+        SdkVersion::V6_1 => Some((
+            program_files_x86().join(r"Microsoft SDKs\Windows\v6.1A"),
+            program_files().join(r"Microsoft SDKs\Windows\v6.1"),
+            "6.1",
+        )),
+        SdkVersion::V6_0 => Some((
+            program_files_x86().join(r"Microsoft SDKs\Windows\v6.0A"),
+            program_files().join(r"Microsoft SDKs\Windows\v6.0"),
+            "6.0",
+        )),
+    }
+}
+
+/// Returns installation information for a Windows SDK in an arbitrary folder, if its layout is
+/// recognised.
+///
+/// See `SdkSearch::with_roots` for the layouts that are recognised. Preview builds of the Windows
+/// 10 SDK are only returned if `include_preview` is set.
+pub fn at_root(root: &Path, include_preview: bool) -> Option<SdkInfo> {
+    if !root.join("Include").is_dir() {
+        trace!("`{}` has no `Include` folder", root.display());
+        return None;
+    }
+    if let Some(info) = find_v10_at(normalize_folder(root.to_owned()), include_preview) {
+        return Some(info);
+    }
+    let info = |product_version: String, flavor| SdkInfo {
+        installation_folder: normalize_folder(root.to_owned()),
+        product_name: None,
+        product_version,
+        bin_dir_from_env: None,
+        flavor,
+        raw_values: BTreeMap::new(),
+        registry_last_write: None,
+    };
+    let name = root
+        .file_name()
+        .and_then(|name| name.to_str())
+        .unwrap_or("");
+    let name = name.trim_start_matches(['v', 'V']);
+    if let Ok(version) = name.parse::<Version>() {
+        if SdkVersion::from_version(&version).is_some_and(|v| v != SdkVersion::V10_0) {
+            let flavor = if name.ends_with('A') || name.ends_with('a') {
+                Flavor::WithA
+            } else {
+                Flavor::Standard
+            };
+            let product_version = format!("{}.{}", version.major, version.minor);
+            return Some(info(product_version, Some(flavor)));
+        }
+    }
+    if root.join(r"Lib\winv6.3").is_dir() {
+        Some(info("8.1".to_owned(), None))
+    } else if root.join(r"Lib\win8").is_dir() {
+        Some(info("8.0".to_owned(), None))
+    } else {
+        None
+    }
+}
+
+/// Returns installation information for the newest Windows 10 SDK build in its installation
+/// folder.
+fn find_v10(include_preview: bool) -> Option<SdkInfo> {
+    find_v10_at(v10_folder(), include_preview)
+}
+
+/// Returns the installation folder of the Windows 10 SDK.
+///
+/// See `kits_folder` for how this is found.
+pub fn v10_folder() -> PathBuf {
+    kits_folder("KitsRoot10", program_files_x86().join(r"Windows Kits\10"))
+}
+
+/// Returns the folder that a Windows Kits release is installed into.
+///
+/// The Windows Kits root can be moved away from `Program Files` when installing, so the folder
+/// recorded by the `Installed Roots` value `name` is used if it can be read, and `default`
+/// otherwise.
+fn kits_folder(name: &str, default: PathBuf) -> PathBuf {
+    registered_or(
+        registry::kits_root(name, RegistryHive::LocalMachine, RegistryView::Any),
+        default,
+    )
+}
+
+/// Returns a registered folder if there is one, or `default` if there is none or it could not be
+/// read.
+fn registered_or(registered: Result<Option<PathBuf>, FindSdkError>, default: PathBuf) -> PathBuf {
+    match registered {
+        Ok(Some(folder)) => normalize_folder(folder),
+        _ => default,
+    }
+}
+
+/// Returns installation information for the newest Windows 10 SDK build in a folder.
+///
+/// Builds are found by looking for versioned subfolders of `Include`. Preview builds are skipped
+/// unless `include_preview` is set.
+fn find_v10_at(installation_folder: PathBuf, include_preview: bool) -> Option<SdkInfo> {
+    let mut info = SdkInfo {
+        installation_folder,
+        product_name: None,
+        product_version: "10.0".to_owned(),
+        bin_dir_from_env: None,
+        flavor: None,
+        raw_values: BTreeMap::new(),
+        registry_last_write: None,
+    };
+    let build = info
+        .build_numbers()
+        .ok()?
+        .into_iter()
+        .find(|build| include_preview || !is_preview_build(build))?;
+    info.product_version = trim_zero_revision(&build);
+    Some(info)
+}
+
+/// Returns the folder that 32-bit programs are installed into.
+fn program_files_x86() -> PathBuf {
+    env::var_os("ProgramFiles(x86)")
+        .or_else(|| env::var_os("ProgramFiles"))
+        .map_or_else(|| PathBuf::from(r"C:\Program Files (x86)"), PathBuf::from)
+}
+
+/// Returns the folder that native programs are installed into.
+///
+/// `ProgramW6432` is checked first, since `ProgramFiles` names the 32-bit folder in 32-bit
+/// processes on 64-bit Windows.
+fn program_files() -> PathBuf {
+    env::var_os("ProgramW6432")
+        .or_else(|| env::var_os("ProgramFiles"))
+        .map_or_else(|| PathBuf::from(r"C:\Program Files"), PathBuf::from)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{at_root, registered_or};
+    use std::env;
+    use std::fs;
+    use std::io;
+    use std::path::PathBuf;
+    use FindSdkError;
+
+    #[test]
+    fn redirected_root() {
+        let default = PathBuf::from(r"C:\Program Files (x86)\Windows Kits\10");
+        let redirected = PathBuf::from(r"D:\Kits\10\");
+        assert_eq!(
+            registered_or(Ok(Some(redirected)), default.clone()),
+            PathBuf::from(r"D:\Kits\10")
+        );
+        assert_eq!(registered_or(Ok(None), default.clone()), default);
+        let denied = FindSdkError::Registry(io::Error::from(io::ErrorKind::PermissionDenied));
+        assert_eq!(registered_or(Err(denied), default.clone()), default);
+    }
+
+    #[test]
+    fn at_root_preview() {
+        let root = env::temp_dir().join("find-winsdk-probe-preview");
+        let res = ["10.0.22621.0", "10.0.25336.1000"]
+            .iter()
+            .try_for_each(|build| fs::create_dir_all(root.join("Include").join(build)));
+        let released = at_root(&root, false).map(|info| info.product_version().to_owned());
+        let preview = at_root(&root, true).map(|info| info.product_version().to_owned());
+        fs::remove_dir_all(&root).expect("could not delete test folder");
+        res.expect("could not create test folder");
+        assert_eq!(released.as_deref(), Some("10.0.22621"));
+        assert_eq!(preview.as_deref(), Some("10.0.25336.1000"));
+    }
+}
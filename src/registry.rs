@@ -0,0 +1,663 @@
+// Copyright (c) 2018 FaultyRAM
+//
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or http://www.apache.org/licenses/LICENSE-2.0> or the
+// MIT license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at
+// your option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Shared helpers for reading the registry.
+//!
+//! Keys are read through a `RegistrySource`, which is normally the Windows registry itself. In
+//! tests it can be replaced by a `MockRegistry`, so that the search logic built on these helpers
+//! can be tested on any platform, without depending on what is installed.
+
+#[cfg(test)]
+use std::cell::{Cell, RefCell};
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+use std::io::{self, ErrorKind};
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+#[cfg(target_os = "windows")]
+use winreg::enums::{
+    HKEY_CURRENT_USER, HKEY_LOCAL_MACHINE, KEY_ENUMERATE_SUB_KEYS, KEY_QUERY_VALUE,
+    KEY_WOW64_32KEY, KEY_WOW64_64KEY,
+};
+#[cfg(target_os = "windows")]
+use winreg::types::FromRegValue;
+#[cfg(target_os = "windows")]
+use winreg::RegKey;
+use {FindSdkError, Version};
+
+/// The key listing the folders that Windows Kits are installed into, along with a subkey for each
+/// installed Windows 10 SDK build.
+pub const INSTALLED_ROOTS_REG_KEY: &str = r"SOFTWARE\Microsoft\Windows Kits\Installed Roots";
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+/// Views of the registry that can be searched for Windows SDK installations.
+///
+/// On 64-bit Windows, 32-bit and 64-bit programs see different views of parts of the registry.
+/// Most Windows SDK installers register themselves in the 32-bit view, but some machines only
+/// have an SDK registered in the 64-bit view.
+pub enum RegistryView {
+    /// Search the 32-bit view, falling back to the 64-bit view for keys that are not found.
+    Any,
+    /// Only search the 32-bit view (`KEY_WOW64_32KEY`).
+    Wow32,
+    /// Only search the 64-bit view (`KEY_WOW64_64KEY`).
+    Wow64,
+}
+
+impl RegistryView {
+    /// Returns each single view to search, in order.
+    fn views(self) -> &'static [RegistryView] {
+        match self {
+            RegistryView::Any => &[RegistryView::Wow32, RegistryView::Wow64],
+            RegistryView::Wow32 => &[RegistryView::Wow32],
+            RegistryView::Wow64 => &[RegistryView::Wow64],
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+/// Registry hives that can be searched for Windows SDK installations.
+///
+/// Windows SDK installers normally register themselves machine-wide, but some locked-down
+/// environments only have an SDK registered for the current user.
+pub enum RegistryHive {
+    /// Only search `HKEY_LOCAL_MACHINE`.
+    LocalMachine,
+    /// Only search `HKEY_CURRENT_USER`.
+    CurrentUser,
+    /// Search `HKEY_LOCAL_MACHINE`, falling back to `HKEY_CURRENT_USER` for keys that are not
+    /// found.
+    Any,
+}
+
+impl RegistryHive {
+    /// Returns each single hive to search, in order.
+    fn hives(self) -> &'static [RegistryHive] {
+        match self {
+            RegistryHive::LocalMachine => &[RegistryHive::LocalMachine],
+            RegistryHive::CurrentUser => &[RegistryHive::CurrentUser],
+            RegistryHive::Any => &[RegistryHive::LocalMachine, RegistryHive::CurrentUser],
+        }
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+/// A value of a registry key, as read from a `RegistrySource`.
+pub enum RegistryValue {
+    /// A string value (`REG_SZ`, `REG_EXPAND_SZ` or `REG_MULTI_SZ`).
+    String(String),
+    /// A numeric value (`REG_DWORD` or `REG_QWORD`).
+    Number(u64),
+    /// A value of any other type, such as binary data.
+    Other,
+}
+
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+/// The contents of a registry key, as read from a `RegistrySource`.
+pub struct RegistryKey {
+    /// The values of the key, by name.
+    pub values: BTreeMap<String, RegistryValue>,
+    /// The names of the key's subkeys.
+    pub subkeys: Vec<String>,
+    /// When the key was last written to, if known.
+    pub last_write: Option<SystemTime>,
+}
+
+impl RegistryKey {
+    /// Returns the value with the given name, ignoring case as the registry does.
+    pub fn value(&self, name: &str) -> Option<&RegistryValue> {
+        self.values
+            .iter()
+            .find(|&(n, _)| n.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value)
+    }
+}
+
+/// Somewhere to read registry keys from.
+pub trait RegistrySource {
+    /// Reads a subkey of a single hive in a single view, returning `None` if it does not exist.
+    ///
+    /// `hive` and `view` are never `Any`; `open_key` tries each hive and view to search in turn.
+    fn read_key(
+        &self,
+        subkey: &str,
+        hive: RegistryHive,
+        view: RegistryView,
+    ) -> io::Result<Option<RegistryKey>>;
+}
+
+#[cfg(target_os = "windows")]
+#[derive(Clone, Copy, Debug)]
+/// The Windows registry.
+pub struct WindowsRegistry;
+
+#[cfg(target_os = "windows")]
+impl RegistrySource for WindowsRegistry {
+    fn read_key(
+        &self,
+        subkey: &str,
+        hive: RegistryHive,
+        view: RegistryView,
+    ) -> io::Result<Option<RegistryKey>> {
+        let predef = match hive {
+            RegistryHive::CurrentUser => HKEY_CURRENT_USER,
+            RegistryHive::LocalMachine | RegistryHive::Any => HKEY_LOCAL_MACHINE,
+        };
+        let flags = match view {
+            RegistryView::Wow64 => KEY_WOW64_64KEY,
+            RegistryView::Wow32 | RegistryView::Any => KEY_WOW64_32KEY,
+        };
+        let key = match RegKey::predef(predef)
+            .open_subkey_with_flags(subkey, KEY_QUERY_VALUE | KEY_ENUMERATE_SUB_KEYS | flags)
+        {
+            Ok(key) => key,
+            Err(ref e) if e.kind() == ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e),
+        };
+        let mut values = BTreeMap::new();
+        for value in key.enum_values() {
+            let (name, value) = value?;
+            let value = String::from_reg_value(&value)
+                .map(RegistryValue::String)
+                .or_else(|_| u32::from_reg_value(&value).map(|n| RegistryValue::Number(n.into())))
+                .or_else(|_| u64::from_reg_value(&value).map(RegistryValue::Number))
+                .unwrap_or(RegistryValue::Other);
+            let _ = values.insert(name, value);
+        }
+        let last_write = key.query_info().ok().and_then(|info| {
+            let time = info.last_write_time;
+            filetime_to_system_time(time.dwLowDateTime, time.dwHighDateTime)
+        });
+        Ok(Some(RegistryKey {
+            values,
+            subkeys: key.enum_keys().collect::<io::Result<_>>()?,
+            last_write,
+        }))
+    }
+}
+
+#[cfg(not(test))]
+/// Calls `f` with the registry source to read keys from.
+fn with_source<T, F: FnOnce(&dyn RegistrySource) -> T>(f: F) -> T {
+    f(&WindowsRegistry)
+}
+
+#[cfg(test)]
+/// Calls `f` with the registry source to read keys from.
+///
+/// This is the mock registry installed on this thread, if any. Otherwise, it is the Windows
+/// registry on Windows, or an empty registry elsewhere.
+fn with_source<T, F: FnOnce(&dyn RegistrySource) -> T>(f: F) -> T {
+    MOCK_REGISTRY.with(|current| match *current.borrow() {
+        Some(ref mock) => f(mock),
+        #[cfg(target_os = "windows")]
+        None => f(&WindowsRegistry),
+        #[cfg(not(target_os = "windows"))]
+        None => f(&MockRegistry::new()),
+    })
+}
+
+/// Returns the names of each hive to search, in order.
+pub fn hive_names(hive: RegistryHive) -> &'static [&'static str] {
+    match hive {
+        RegistryHive::LocalMachine => &["HKEY_LOCAL_MACHINE"],
+        RegistryHive::CurrentUser => &["HKEY_CURRENT_USER"],
+        RegistryHive::Any => &["HKEY_LOCAL_MACHINE", "HKEY_CURRENT_USER"],
+    }
+}
+
+/// Reads a subkey of the given hive, returning `None` if it does not exist.
+pub fn open_key(
+    subkey: &str,
+    hive: RegistryHive,
+    view: RegistryView,
+) -> Result<Option<RegistryKey>, FindSdkError> {
+    with_source(|source| {
+        for hive in hive.hives() {
+            for view in view.views() {
+                match source.read_key(subkey, *hive, *view) {
+                    Ok(Some(key)) => {
+                        debug!("opened registry key `{subkey}`");
+                        return Ok(Some(key));
+                    }
+                    Ok(None) => (),
+                    Err(e) => {
+                        debug!("could not open registry key `{subkey}`: {e}");
+                        return Err(FindSdkError::Registry(e));
+                    }
+                }
+            }
+        }
+        trace!("registry key `{subkey}` does not exist");
+        Ok(None)
+    })
+}
+
+/// Reads a string value from a registry key, returning `None` if it does not exist.
+pub fn get_string(key: &RegistryKey, name: &str) -> Result<Option<String>, FindSdkError> {
+    match key.value(name) {
+        Some(RegistryValue::String(value)) => Ok(Some(value.clone())),
+        Some(_) => Err(FindSdkError::Registry(io::Error::new(
+            ErrorKind::InvalidData,
+            format!("registry value `{name}` is not a string"),
+        ))),
+        None => Ok(None),
+    }
+}
+
+/// Reads every value of a registry key that can be represented as a string.
+///
+/// String values are read as they are, and numeric values are formatted in decimal. Values of
+/// other types, such as binary data, are skipped.
+pub fn read_values(key: &RegistryKey) -> BTreeMap<String, String> {
+    key.values
+        .iter()
+        .filter_map(|(name, value)| match *value {
+            RegistryValue::String(ref text) => Some((name.clone(), text.clone())),
+            RegistryValue::Number(n) => Some((name.clone(), n.to_string())),
+            RegistryValue::Other => None,
+        })
+        .collect()
+}
+
+/// Converts a Windows `FILETIME`, given as its low and high halves, to a `SystemTime`.
+///
+/// A `FILETIME` counts 100-nanosecond intervals since the start of 1601 (UTC). Returns `None` for
+/// times that cannot be represented.
+fn filetime_to_system_time(low: u32, high: u32) -> Option<SystemTime> {
+    /// The number of 100-nanosecond intervals between the start of 1601 and the Unix epoch.
+    const UNIX_EPOCH_TICKS: u64 = 116_444_736_000_000_000;
+    let ticks = u64::from(high) << 32 | u64::from(low);
+    let since_epoch = |ticks: u64| {
+        Duration::from_secs(ticks / 10_000_000) + Duration::from_nanos(ticks % 10_000_000 * 100)
+    };
+    if ticks >= UNIX_EPOCH_TICKS {
+        UNIX_EPOCH.checked_add(since_epoch(ticks - UNIX_EPOCH_TICKS))
+    } else {
+        UNIX_EPOCH.checked_sub(since_epoch(UNIX_EPOCH_TICKS - ticks))
+    }
+}
+
+/// Returns the folder that the Windows 10 SDK and related kits are installed into, if any.
+pub fn kits_root10(
+    hive: RegistryHive,
+    view: RegistryView,
+) -> Result<Option<PathBuf>, FindSdkError> {
+    kits_root("KitsRoot10", hive, view)
+}
+
+/// Returns the folder recorded by a value of `Installed Roots`, such as `KitsRoot81`, if any.
+pub fn kits_root(
+    name: &str,
+    hive: RegistryHive,
+    view: RegistryView,
+) -> Result<Option<PathBuf>, FindSdkError> {
+    match open_key(INSTALLED_ROOTS_REG_KEY, hive, view)? {
+        Some(key) => Ok(get_string(&key, name)?.map(PathBuf::from)),
+        None => Ok(None),
+    }
+}
+
+/// Returns the build numbers of every Windows 10 SDK build registered under `Installed Roots`,
+/// newest first.
+pub fn installed_v10_builds(
+    hive: RegistryHive,
+    view: RegistryView,
+) -> Result<Vec<String>, FindSdkError> {
+    let Some(key) = open_key(INSTALLED_ROOTS_REG_KEY, hive, view)? else {
+        return Ok(Vec::new());
+    };
+    let mut builds = Vec::new();
+    for name in key.subkeys {
+        if let Ok(version) = name.parse::<Version>() {
+            if version.major == 10 {
+                builds.push((version, name));
+            }
+        }
+    }
+    builds.sort_by_key(|&(version, _)| Reverse(version));
+    Ok(builds.into_iter().map(|(_, name)| name).collect())
+}
+
+#[cfg(test)]
+thread_local! {
+    /// The mock registry installed on this thread by `MockRegistry::install`, if any.
+    static MOCK_REGISTRY: RefCell<Option<MockRegistry>> = const { RefCell::new(None) };
+}
+
+#[cfg(test)]
+#[derive(Clone, Debug, Default)]
+/// A registry for tests, holding only the keys that were added to it.
+///
+/// Keys are looked up ignoring case. A key that was not added itself, but has subkeys that were,
+/// exists with no values.
+pub struct MockRegistry {
+    keys: Vec<(RegistryHive, RegistryView, String, RegistryKey)>,
+    errors: Vec<MockError>,
+}
+
+#[cfg(test)]
+#[derive(Clone, Debug)]
+/// An error that a `MockRegistry` returns when reading a key.
+struct MockError {
+    hive: RegistryHive,
+    view: RegistryView,
+    subkey: String,
+    kind: ErrorKind,
+    /// An OS error code to return instead of an error of kind `kind`, as `winreg` does.
+    os_error: Option<i32>,
+    /// How many more times the error is returned, or `None` if it is always returned.
+    remaining: Option<Cell<u32>>,
+}
+
+#[cfg(test)]
+impl MockRegistry {
+    /// Creates an empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a key with the given string values to `HKEY_LOCAL_MACHINE`, in the 32-bit view.
+    pub fn with_key(self, subkey: &str, values: &[(&str, &str)]) -> Self {
+        self.with_key_in(
+            RegistryHive::LocalMachine,
+            RegistryView::Wow32,
+            subkey,
+            values,
+        )
+    }
+
+    /// Adds a key with the given string values to the given hive and view.
+    pub fn with_key_in(
+        self,
+        hive: RegistryHive,
+        view: RegistryView,
+        subkey: &str,
+        values: &[(&str, &str)],
+    ) -> Self {
+        let values = values
+            .iter()
+            .map(|&(name, value)| (name.to_owned(), RegistryValue::String(value.to_owned())))
+            .collect();
+        self.with_raw_key(
+            hive,
+            view,
+            subkey,
+            RegistryKey {
+                values,
+                ..RegistryKey::default()
+            },
+        )
+    }
+
+    /// Adds a key with the given contents to the given hive and view.
+    pub fn with_raw_key(
+        mut self,
+        hive: RegistryHive,
+        view: RegistryView,
+        subkey: &str,
+        key: RegistryKey,
+    ) -> Self {
+        self.keys.push((hive, view, subkey.to_owned(), key));
+        self
+    }
+
+    /// Makes reading a key of `HKEY_LOCAL_MACHINE`, in the 32-bit view, fail with an error of the
+    /// given kind.
+    pub fn with_error(self, subkey: &str, kind: ErrorKind) -> Self {
+        self.with_failure(subkey, kind, None, None)
+    }
+
+    /// Like `with_error`, but only the first `times` attempts to read the key fail.
+    pub fn with_transient_error(self, subkey: &str, kind: ErrorKind, times: u32) -> Self {
+        self.with_failure(subkey, kind, None, Some(times))
+    }
+
+    /// Like `with_transient_error`, but the error has the given OS error code, as the errors that
+    /// `winreg` returns do.
+    pub fn with_transient_os_error(self, subkey: &str, code: i32, times: u32) -> Self {
+        self.with_failure(subkey, ErrorKind::Other, Some(code), Some(times))
+    }
+
+    fn with_failure(
+        mut self,
+        subkey: &str,
+        kind: ErrorKind,
+        os_error: Option<i32>,
+        times: Option<u32>,
+    ) -> Self {
+        self.errors.push(MockError {
+            hive: RegistryHive::LocalMachine,
+            view: RegistryView::Wow32,
+            subkey: subkey.to_owned(),
+            kind,
+            os_error,
+            remaining: times.map(Cell::new),
+        });
+        self
+    }
+
+    /// Makes this the registry that keys are read from on the current thread, until the returned
+    /// guard is dropped.
+    pub fn install(self) -> MockGuard {
+        MOCK_REGISTRY.with(|current| *current.borrow_mut() = Some(self));
+        MockGuard { _priv: () }
+    }
+}
+
+#[cfg(test)]
+impl RegistrySource for MockRegistry {
+    fn read_key(
+        &self,
+        subkey: &str,
+        hive: RegistryHive,
+        view: RegistryView,
+    ) -> io::Result<Option<RegistryKey>> {
+        let matches = |h: RegistryHive, v: RegistryView| h == hive && v == view;
+        let error = self.errors.iter().find(|error| {
+            matches(error.hive, error.view)
+                && error.subkey.eq_ignore_ascii_case(subkey)
+                && error.remaining.as_ref().is_none_or(|n| n.get() > 0)
+        });
+        if let Some(error) = error {
+            if let Some(ref remaining) = error.remaining {
+                remaining.set(remaining.get() - 1);
+            }
+            return Err(error.os_error.map_or_else(
+                || io::Error::new(error.kind, "mock registry error"),
+                io::Error::from_raw_os_error,
+            ));
+        }
+        let prefix = format!("{}\\", subkey.to_ascii_lowercase());
+        let mut found: Option<RegistryKey> = None;
+        for &(h, v, ref path, ref key) in &self.keys {
+            if !matches(h, v) {
+                continue;
+            }
+            if path.eq_ignore_ascii_case(subkey) {
+                let found = found.get_or_insert_with(RegistryKey::default);
+                found.values = key.values.clone();
+                found.subkeys.extend(key.subkeys.iter().cloned());
+                found.last_write = key.last_write;
+            } else if path.to_ascii_lowercase().starts_with(&prefix) {
+                let child = path[prefix.len()..].split('\\').next().unwrap_or("");
+                let found = found.get_or_insert_with(RegistryKey::default);
+                if !found.subkeys.iter().any(|s| s.eq_ignore_ascii_case(child)) {
+                    found.subkeys.push(child.to_owned());
+                }
+            }
+        }
+        Ok(found)
+    }
+}
+
+#[cfg(test)]
+#[derive(Debug)]
+/// Stops using a mock registry when dropped.
+pub struct MockGuard {
+    _priv: (),
+}
+
+#[cfg(test)]
+impl Drop for MockGuard {
+    fn drop(&mut self) {
+        MOCK_REGISTRY.with(|current| *current.borrow_mut() = None);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        filetime_to_system_time, get_string, installed_v10_builds, open_key, read_values,
+        MockRegistry, RegistryKey, RegistryValue, INSTALLED_ROOTS_REG_KEY,
+    };
+    use std::io::ErrorKind;
+    #[cfg(target_os = "windows")]
+    use std::time::SystemTime;
+    use std::time::{Duration, UNIX_EPOCH};
+    #[cfg(target_os = "windows")]
+    use winreg::enums::HKEY_CURRENT_USER;
+    #[cfg(target_os = "windows")]
+    use winreg::RegKey;
+    use {FindSdkError, RegistryHive, RegistryView};
+
+    #[test]
+    #[cfg(target_os = "windows")]
+    fn explicit_hive() {
+        let path = r"Software\find-winsdk\tests\explicit_hive";
+        let hkcu = RegKey::predef(HKEY_CURRENT_USER);
+        let _ = hkcu
+            .create_subkey(path)
+            .expect("could not create test registry key");
+        let open = |hive| {
+            open_key(path, hive, RegistryView::Any)
+                .map(|key| key.is_some())
+                .expect("could not read test registry key")
+        };
+        let res = (
+            open(RegistryHive::CurrentUser),
+            open(RegistryHive::Any),
+            open(RegistryHive::LocalMachine),
+        );
+        hkcu.delete_subkey_all(path)
+            .expect("could not delete test registry key");
+        assert_eq!(res, (true, true, false));
+    }
+
+    #[test]
+    fn mock_fallback() {
+        let _mock = MockRegistry::new()
+            .with_key_in(
+                RegistryHive::CurrentUser,
+                RegistryView::Wow64,
+                r"Software\Test\User64",
+                &[("Name", "user64")],
+            )
+            .with_key(r"Software\Test\Machine32\Child", &[])
+            .with_error(r"Software\Test\Denied", ErrorKind::PermissionDenied)
+            .install();
+        let open = |path, hive, view| {
+            open_key(path, hive, view)
+                .expect("could not read mock registry key")
+                .is_some()
+        };
+        let user64 = r"software\test\USER64";
+        assert!(open(user64, RegistryHive::Any, RegistryView::Any));
+        assert!(open(user64, RegistryHive::CurrentUser, RegistryView::Wow64));
+        assert!(!open(user64, RegistryHive::LocalMachine, RegistryView::Any));
+        assert!(!open(user64, RegistryHive::Any, RegistryView::Wow32));
+        let parent = open_key(
+            r"Software\Test\Machine32",
+            RegistryHive::LocalMachine,
+            RegistryView::Any,
+        )
+        .expect("could not read mock registry key")
+        .expect("implicit parent key does not exist");
+        assert_eq!(parent.subkeys, ["Child"]);
+        match open_key(
+            r"Software\Test\Denied",
+            RegistryHive::Any,
+            RegistryView::Any,
+        ) {
+            Err(FindSdkError::Registry(ref e)) => assert_eq!(e.kind(), ErrorKind::PermissionDenied),
+            other => panic!("expected a registry error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn values() {
+        let mut key = RegistryKey::default();
+        let _ = key.values.insert(
+            "InstallationFolder".to_owned(),
+            RegistryValue::String(r"C:\sdk".to_owned()),
+        );
+        let _ = key
+            .values
+            .insert("ManifestEnabled".to_owned(), RegistryValue::Number(1));
+        let _ = key.values.insert("Blob".to_owned(), RegistryValue::Other);
+        assert_eq!(
+            get_string(&key, "installationfolder").expect("value is a string"),
+            Some(r"C:\sdk".to_owned())
+        );
+        assert_eq!(
+            get_string(&key, "ProductName").expect("value is absent"),
+            None
+        );
+        assert!(get_string(&key, "ManifestEnabled").is_err());
+        let values = read_values(&key);
+        assert_eq!(values.len(), 2);
+        assert_eq!(values["ManifestEnabled"], "1");
+    }
+
+    #[test]
+    fn v10_builds() {
+        let _mock = MockRegistry::new()
+            .with_key(INSTALLED_ROOTS_REG_KEY, &[("KitsRoot10", r"C:\Kits\10\")])
+            .with_key(&format!(r"{INSTALLED_ROOTS_REG_KEY}\10.0.17763.0"), &[])
+            .with_key(&format!(r"{INSTALLED_ROOTS_REG_KEY}\10.0.19041.0"), &[])
+            .with_key(&format!(r"{INSTALLED_ROOTS_REG_KEY}\8.1.0.0"), &[])
+            .with_key(&format!(r"{INSTALLED_ROOTS_REG_KEY}\not-a-build"), &[])
+            .install();
+        assert_eq!(
+            installed_v10_builds(RegistryHive::LocalMachine, RegistryView::Any)
+                .expect("could not read mock registry"),
+            ["10.0.19041.0", "10.0.17763.0"]
+        );
+    }
+
+    #[test]
+    fn filetime() {
+        assert_eq!(
+            filetime_to_system_time(0xd53e_8000, 0x019d_b1de),
+            Some(UNIX_EPOCH)
+        );
+        assert_eq!(
+            filetime_to_system_time(0xd53e_8000 + 10, 0x019d_b1de),
+            UNIX_EPOCH.checked_add(Duration::from_micros(1))
+        );
+        assert!(filetime_to_system_time(0, 0).is_none_or(|time| time < UNIX_EPOCH));
+    }
+
+    #[test]
+    #[cfg(target_os = "windows")]
+    fn last_write() {
+        let path = r"Software\find-winsdk\tests\last_write";
+        let hkcu = RegKey::predef(HKEY_CURRENT_USER);
+        let before = SystemTime::now() - Duration::from_mins(1);
+        let _ = hkcu
+            .create_subkey(path)
+            .expect("could not create test registry key");
+        let key = open_key(path, RegistryHive::CurrentUser, RegistryView::Any);
+        hkcu.delete_subkey_all(path)
+            .expect("could not delete test registry key");
+        let key = key
+            .expect("could not read test registry key")
+            .expect("test registry key does not exist");
+        assert!(key.last_write.expect("could not read last write time") > before);
+    }
+}
@@ -0,0 +1,88 @@
+// Copyright (c) 2018 FaultyRAM
+//
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or http://www.apache.org/licenses/LICENSE-2.0> or the
+// MIT license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at
+// your option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Reports describing how a search for a Windows SDK installation went.
+
+use std::path::PathBuf;
+use SdkVersion;
+
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+/// A record of each version searched for by `SdkSearch::find_with_report`, and what was found.
+pub struct SearchReport {
+    pub(crate) steps: Vec<SearchStep>,
+}
+
+impl SearchReport {
+    #[must_use]
+    /// Returns each version that was searched for, in the order they were searched.
+    pub fn steps(&self) -> &[SearchStep] {
+        &self.steps
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+/// A single version searched for as part of a search.
+pub struct SearchStep {
+    pub(crate) version: SdkVersion,
+    pub(crate) locations: Vec<String>,
+    pub(crate) outcome: SearchOutcome,
+}
+
+impl SearchStep {
+    #[must_use]
+    /// Returns the version that was searched for.
+    pub fn version(&self) -> SdkVersion {
+        self.version
+    }
+
+    #[must_use]
+    /// Returns the places that were consulted, such as registry keys, folders and environment
+    /// variables, in the order they were consulted.
+    pub fn locations(&self) -> &[String] {
+        &self.locations
+    }
+
+    #[must_use]
+    /// Returns what was found.
+    pub fn outcome(&self) -> &SearchOutcome {
+        &self.outcome
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+/// The result of searching for a single version.
+pub enum SearchOutcome {
+    /// The version was not searched for, because of the search options, e.g. because it is older
+    /// than the minimum version or environment variables are not used.
+    Skipped,
+    /// No installation was found.
+    NotFound,
+    /// A registry key for the version exists, but its values could not be read.
+    Malformed {
+        /// The path to the registry key.
+        key: String,
+    },
+    /// An installation was found, but its installation folder does not exist.
+    FolderMissing {
+        /// The installation folder that does not exist.
+        folder: PathBuf,
+    },
+    /// An installation was found, but it does not have the headers needed to compile C or C++
+    /// code.
+    HeadersMissing {
+        /// The installation folder of the installation.
+        folder: PathBuf,
+    },
+    /// The search failed with an error, which is returned alongside the report.
+    Failed,
+    /// An installation was found.
+    Found {
+        /// The installation folder of the installation.
+        folder: PathBuf,
+    },
+}
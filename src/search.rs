@@ -0,0 +1,2327 @@
+// Copyright (c) 2018 FaultyRAM
+//
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or http://www.apache.org/licenses/LICENSE-2.0> or the
+// MIT license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at
+// your option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Configurable searches for Windows SDK installations.
+
+use probe;
+use registry::{self, RegistryHive, RegistryKey, RegistryValue, RegistryView};
+use report::{SearchOutcome, SearchReport, SearchStep};
+use std::collections::BTreeMap;
+use std::env;
+use std::ffi::{OsStr, OsString};
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::Duration;
+use std::vec;
+#[cfg(feature = "vs-setup")]
+use vs_setup;
+use {normalize_folder, FindSdkError, SdkInfo, SdkVersion, Version, ANY_SEARCH_ORDER};
+
+const V10_0_REG_KEY: &str = r"SOFTWARE\Microsoft\Microsoft SDKs\Windows\v10.0";
+const V8_1A_REG_KEY: &str = r"SOFTWARE\Microsoft\Microsoft SDKs\Windows\v8.1A";
+const V8_1_REG_KEY: &str = r"SOFTWARE\Microsoft\Microsoft SDKs\Windows\v8.1";
+const V8_0A_REG_KEY: &str = r"SOFTWARE\Microsoft\Microsoft SDKs\Windows\v8.0A";
+const V8_0_REG_KEY: &str = r"SOFTWARE\Microsoft\Microsoft SDKs\Windows\v8.0";
+const V7_1A_REG_KEY: &str = r"SOFTWARE\Microsoft\Microsoft SDKs\Windows\v7.1A";
+const V7_1_REG_KEY: &str = r"SOFTWARE\Microsoft\Microsoft SDKs\Windows\v7.1";
+const V7_0A_REG_KEY: &str = r"SOFTWARE\Microsoft\Microsoft SDKs\Windows\v7.0a";
+const V7_0_REG_KEY: &str = r"SOFTWARE\Microsoft\Microsoft SDKs\Windows\v7.0";
+const V6_1A_REG_KEY: &str = r"SOFTWARE\Microsoft\Microsoft SDKs\Windows\v6.1a";
+const V6_1_REG_KEY: &str = r"SOFTWARE\Microsoft\Microsoft SDKs\Windows\v6.1";
+const V6_0A_REG_KEY: &str = r"SOFTWARE\Microsoft\Microsoft SDKs\Windows\v6.0a";
+const V6_0_REG_KEY: &str = r"SOFTWARE\Microsoft\Microsoft SDKs\Windows\v6.0";
+
+/// The kinds of registry error that a search across several versions tolerates.
+///
+/// A version whose registry keys cannot be read for one of these reasons is skipped, unless every
+/// version searched fails in this way.
+const TOLERATED_ERROR_KINDS: &[ErrorKind] = &[ErrorKind::PermissionDenied];
+
+/// The kinds of registry error that are never retried, even if `SdkSearch::retries` allows it.
+///
+/// A missing key or denied access will not go away on its own. Every other error is retried, since
+/// `winreg` reports most Win32 errors with a raw OS error code, which rarely maps to a more
+/// specific kind than `ErrorKind::Uncategorized`.
+const NEVER_RETRIED_ERROR_KINDS: &[ErrorKind] = &[ErrorKind::NotFound, ErrorKind::PermissionDenied];
+
+/// How long to wait before retrying a registry key after a transient error.
+const RETRY_DELAY: Duration = Duration::from_millis(50);
+
+/// The component subkeys that are searched, in order, when a release's registry key cannot be read.
+///
+/// Some v7.x installations leave their version key empty and record their details in these
+/// instead.
+pub const DEFAULT_COMPONENT_SUBKEYS: &[&str] = &[
+    "WinSDKBuild",
+    "WinSDKTools",
+    "WinSDK-NetFx40Tools",
+    "WinSDK-NetFx35Tools",
+];
+
+/// The environment variable naming a version for `SdkVersion::Any` to search for first.
+const PREFER_ENV_VAR: &str = "FIND_WINSDK_PREFER";
+
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize)]
+/// Flavours of Windows SDK releases prior to v10.0.
+///
+/// Each of these releases came in two flavours: a standalone one, and one with an `A` suffix in
+/// its version number that was typically bundled with Visual Studio.
+pub enum Flavor {
+    /// The standalone flavour, e.g. v8.1.
+    Standard,
+    /// The flavour with an `A` suffix, e.g. v8.1A.
+    WithA,
+}
+
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+/// Which flavours to search for, for releases that come in two flavours.
+///
+/// This has no effect on releases that only come in one flavour, such as v10.0.
+pub enum FlavorPreference {
+    /// Search for the `A` flavour first, then the standard flavour.
+    PreferA,
+    /// Search for the standard flavour first, then the `A` flavour.
+    PreferStandard,
+    /// Only search for the `A` flavour.
+    OnlyA,
+    /// Only search for the standard flavour.
+    OnlyStandard,
+}
+
+impl FlavorPreference {
+    /// Returns the flavour that is searched for first.
+    fn preferred(self) -> Flavor {
+        match self {
+            FlavorPreference::PreferA | FlavorPreference::OnlyA => Flavor::WithA,
+            FlavorPreference::PreferStandard | FlavorPreference::OnlyStandard => Flavor::Standard,
+        }
+    }
+
+    /// Returns whether the flavour that is not preferred is never searched for.
+    fn is_exclusive(self) -> bool {
+        match self {
+            FlavorPreference::OnlyA | FlavorPreference::OnlyStandard => true,
+            FlavorPreference::PreferA | FlavorPreference::PreferStandard => false,
+        }
+    }
+
+    /// Returns whether an installation of the given flavour, if any, is acceptable.
+    fn allows(self, flavor: Option<Flavor>) -> bool {
+        !self.is_exclusive() || flavor.is_none_or(|flavor| flavor == self.preferred())
+    }
+}
+
+impl From<Flavor> for FlavorPreference {
+    /// Returns the preference for a flavour that still falls back to the other one.
+    fn from(flavor: Flavor) -> Self {
+        match flavor {
+            Flavor::Standard => FlavorPreference::PreferStandard,
+            Flavor::WithA => FlavorPreference::PreferA,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+/// Options for searching for a Windows SDK installation.
+///
+/// This allows more control over detection than `SdkInfo::find`, which is equivalent to
+/// `SdkSearch::new().version(version).find()`.
+pub struct SdkSearch {
+    version: SdkVersion,
+    min_version: SdkVersion,
+    exclude: Vec<SdkVersion>,
+    flavor: FlavorPreference,
+    registry_hive: RegistryHive,
+    registry_view: RegistryView,
+    env: EnvOptions,
+    folder_checks: FolderChecks,
+    filesystem_only: bool,
+    retries: u32,
+    include_preview: bool,
+    component_subkeys: Vec<String>,
+    roots: Vec<PathBuf>,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+/// The options of an `SdkSearch` that control how environment variables are used.
+struct EnvOptions {
+    enabled: bool,
+    dir_var: String,
+    version_var: String,
+    enrich: bool,
+    use_prefer_var: bool,
+}
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+/// The options of an `SdkSearch` that control how installation folders are checked.
+struct FolderChecks {
+    require_existing: bool,
+    require_headers: bool,
+    canonicalize: bool,
+}
+
+impl SdkSearch {
+    #[must_use]
+    /// Creates a new set of search options.
+    ///
+    /// By default, any version is searched for with no minimum, preferring `Flavor::WithA`, using
+    /// `RegistryHive::LocalMachine`, `RegistryView::Any` and environment variables, honouring
+    /// `FIND_WINSDK_PREFER`, and without checking that installation folders exist.
+    pub fn new() -> Self {
+        Self {
+            version: SdkVersion::Any,
+            min_version: SdkVersion::Any,
+            exclude: Vec::new(),
+            flavor: FlavorPreference::PreferA,
+            registry_hive: RegistryHive::LocalMachine,
+            registry_view: RegistryView::Any,
+            env: EnvOptions {
+                enabled: true,
+                dir_var: "WindowsSdkDir".to_owned(),
+                version_var: "WindowsSdkVersion".to_owned(),
+                enrich: false,
+                use_prefer_var: true,
+            },
+            folder_checks: FolderChecks::default(),
+            filesystem_only: false,
+            retries: 0,
+            include_preview: false,
+            component_subkeys: DEFAULT_COMPONENT_SUBKEYS
+                .iter()
+                .map(|subkey| (*subkey).to_owned())
+                .collect(),
+            roots: Vec::new(),
+        }
+    }
+
+    /// Sets the Windows SDK version to search for.
+    pub fn version(&mut self, version: SdkVersion) -> &mut Self {
+        self.version = version;
+        self
+    }
+
+    /// Sets the oldest Windows SDK version to accept.
+    ///
+    /// Concrete versions older than `min` are never searched for. An installation specified by
+    /// environment variables is only accepted if its version number belongs to a release at least
+    /// as new as `min`. If `min` is `SdkVersion::Any` or `SdkVersion::Env`, there is no minimum.
+    pub fn min_version(&mut self, min: SdkVersion) -> &mut Self {
+        self.min_version = min;
+        self
+    }
+
+    /// Sets versions for `SdkVersion::Any` to leave out of its search.
+    ///
+    /// The registry keys and folders of these versions are never consulted by `find`, `find_all`
+    /// or the like when searching for `SdkVersion::Any`, and this combines with `min_version`, so
+    /// that e.g. `SdkVersion::V8_0` can be excluded while also excluding everything before v7.1.
+    /// A version set with `version` is still searched for even if it is excluded.
+    pub fn exclude(&mut self, versions: &[SdkVersion]) -> &mut Self {
+        self.exclude = versions.to_vec();
+        self
+    }
+
+    /// Sets which flavour to prefer for releases that come in two flavours.
+    ///
+    /// The other flavour is still searched for if the preferred one is not found; use
+    /// `flavor_preference` to search for only one flavour.
+    pub fn prefer_flavor(&mut self, flavor: Flavor) -> &mut Self {
+        self.flavor = flavor.into();
+        self
+    }
+
+    /// Sets which flavours to search for, and in which order, for releases that come in two
+    /// flavours.
+    pub fn flavor_preference(&mut self, preference: FlavorPreference) -> &mut Self {
+        self.flavor = preference;
+        self
+    }
+
+    /// Sets the registry hive to search.
+    ///
+    /// Use `RegistryHive::Any` to fall back to SDKs registered for the current user when none are
+    /// registered machine-wide.
+    pub fn registry_hive(&mut self, hive: RegistryHive) -> &mut Self {
+        self.registry_hive = hive;
+        self
+    }
+
+    /// Sets the view of the registry to search.
+    pub fn registry_view(&mut self, view: RegistryView) -> &mut Self {
+        self.registry_view = view;
+        self
+    }
+
+    /// Sets whether to use a Windows SDK installation specified by environment variables.
+    ///
+    /// If this is `false`, `SdkVersion::Any` only searches the registry, and `SdkVersion::Env`
+    /// never finds anything.
+    pub fn use_env(&mut self, use_env: bool) -> &mut Self {
+        self.env.enabled = use_env;
+        self
+    }
+
+    /// Sets the names of the environment variables that give the installation folder and version
+    /// number of a Windows SDK installation.
+    ///
+    /// By default, these are `WindowsSdkDir` and `WindowsSdkVersion`, as set by Visual Studio
+    /// developer command prompts. Other build systems may use different names. The
+    /// `WindowsSdkVerBinPath` variable is always used as it is.
+    pub fn env_var_names(&mut self, dir: &str, version: &str) -> &mut Self {
+        dir.clone_into(&mut self.env.dir_var);
+        version.clone_into(&mut self.env.version_var);
+        self
+    }
+
+    /// Sets whether to fill in details of an installation specified by environment variables from
+    /// the registry.
+    ///
+    /// Environment variables only give an installation folder and a version number. If this is
+    /// `true`, the registry is searched for an installation in the same folder, and its product
+    /// name, flavour and raw values are used. Its version number is also used if it refines the
+    /// one given by environment variables, e.g. `8.1.25984` for `8.1`; otherwise, e.g. for a
+    /// different Windows 10 SDK build, the version from the environment is kept.
+    pub fn enrich_env(&mut self, enrich: bool) -> &mut Self {
+        self.env.enrich = enrich;
+        self
+    }
+
+    /// Sets whether to skip installations whose installation folder does not exist.
+    ///
+    /// Uninstalled SDKs sometimes leave registry keys behind that point to deleted folders. If
+    /// this is `true`, such installations are treated as if they were not found.
+    pub fn require_existing_folder(&mut self, require: bool) -> &mut Self {
+        self.folder_checks.require_existing = require;
+        self
+    }
+
+    /// Sets whether to skip installations that do not have the headers needed to compile C or C++
+    /// code.
+    ///
+    /// Some installations only include tools. If this is `true`, installations for which
+    /// `SdkInfo::has_headers` returns `false` are treated as if they were not found.
+    pub fn require_headers(&mut self, require: bool) -> &mut Self {
+        self.folder_checks.require_headers = require;
+        self
+    }
+
+    /// Sets whether to look for installations at their default locations instead of searching the
+    /// registry.
+    ///
+    /// This is useful where the registry cannot be read, e.g. in some sandboxed environments. If
+    /// this is `true`, SDK registry keys are never read, and Windows 10 SDK builds are found by
+    /// looking for versioned subfolders of `Include`. Environment variables are still used if
+    /// enabled. Since the Windows Kits root can be moved when installing, the folders recorded
+    /// under `Windows Kits\Installed Roots` (e.g. `KitsRoot10`) are still used in place of the
+    /// default locations if they can be read.
+    pub fn filesystem_only(&mut self, filesystem_only: bool) -> &mut Self {
+        self.filesystem_only = filesystem_only;
+        self
+    }
+
+    /// Sets whether to canonicalize the installation folders of installations that are found.
+    ///
+    /// The folder recorded in the registry can name a different drive than the one the folder is
+    /// accessed through, e.g. with drives substituted by `subst` or some mapped network drives.
+    /// If this is `true`, installation folders are resolved with `std::fs::canonicalize`, so that
+    /// they can be compared with other canonical paths. Canonical paths use the `\\?\` prefix.
+    /// Folders that cannot be canonicalized, e.g. because they do not exist, are kept as they are.
+    pub fn canonicalize_paths(&mut self, canonicalize: bool) -> &mut Self {
+        self.folder_checks.canonicalize = canonicalize;
+        self
+    }
+
+    /// Sets how many times to retry reading a registry key after a transient error.
+    ///
+    /// Errors are retried after a short delay, except those of the kinds `NotFound` and
+    /// `PermissionDenied`, which will not go away on their own. If every attempt fails, the last
+    /// error is returned as usual. By default, nothing is retried.
+    pub fn retries(&mut self, retries: u32) -> &mut Self {
+        self.retries = retries;
+        self
+    }
+
+    /// Sets whether preview builds of the Windows 10 SDK are found.
+    ///
+    /// This applies to `find_all_v10`, and to the build chosen when the Windows 10 SDK is found
+    /// through `Windows Kits\Installed Roots`, `filesystem_only` or `with_roots`.
+    ///
+    /// Released builds always have a zero fourth component, e.g. `10.0.19041.0`, so any build
+    /// whose folder name has a non-zero fourth component, e.g. `10.0.25336.1000`, is treated as a
+    /// preview build, such as one from the Windows Insider Program. By default, preview builds
+    /// are left out.
+    pub fn include_preview(&mut self, include: bool) -> &mut Self {
+        self.include_preview = include;
+        self
+    }
+
+    /// Sets whether `SdkVersion::Any` honours the `FIND_WINSDK_PREFER` environment variable.
+    ///
+    /// If this is `true` and `FIND_WINSDK_PREFER` names a version, e.g. `v8.1` or `10.0`, that
+    /// version is searched for first, followed by the others in the usual order. This lets a
+    /// build pin a version without changing code. Values that do not name a version are ignored.
+    pub fn use_prefer_var(&mut self, use_prefer_var: bool) -> &mut Self {
+        self.env.use_prefer_var = use_prefer_var;
+        self
+    }
+
+    /// Sets the component subkeys to search when the registry key of a release that comes in two
+    /// flavours cannot be read.
+    ///
+    /// Some v7.x installations leave their version key (e.g. `v7.1`) without an
+    /// `InstallationFolder` value, and record it only in a component subkey such as
+    /// `WinSDK-NetFx40Tools`. The first of these subkeys that can be read is used instead. Since
+    /// component folders are usually subfolders of the installation folder, the nearest folder at
+    /// or above the recorded one that has an `Include` subfolder is used as the installation
+    /// folder, if there is one. By default, `DEFAULT_COMPONENT_SUBKEYS` are searched; pass an empty
+    /// slice to disable this.
+    pub fn component_subkeys(&mut self, subkeys: &[&str]) -> &mut Self {
+        self.component_subkeys = subkeys.iter().map(|subkey| (*subkey).to_owned()).collect();
+        self
+    }
+
+    /// Sets folders to treat as Windows SDK installations, in addition to those that are
+    /// registered.
+    ///
+    /// This allows SDKs that were copied or extracted to a folder, rather than installed, to be
+    /// found. For each version searched for, these folders are tried in order before the registry
+    /// (or default locations, if `filesystem_only` is set). Each folder must have an `Include`
+    /// subfolder, and is recognised as:
+    ///
+    /// * the Windows 10 SDK, if `Include` has subfolders named after builds, e.g. `10.0.17763.0`;
+    ///   the newest build is used;
+    /// * the release named by the folder itself, e.g. `8.1`, `v7.1` or `v7.0A`;
+    /// * v8.1 or v8.0, if `Lib` has a `winv6.3` or `win8` subfolder respectively.
+    ///
+    /// Folders that are not recognised are ignored.
+    pub fn with_roots(&mut self, roots: &[PathBuf]) -> &mut Self {
+        self.roots = roots.to_vec();
+        self
+    }
+
+    /// Returns installation information for a Windows SDK installation matching these options.
+    ///
+    /// # Errors
+    ///
+    /// Returns `FindSdkError::Registry` if the registry cannot be read, and
+    /// `FindSdkError::InvalidEnvVar` if an environment variable describing an SDK has an invalid
+    /// value. If the registry key for a specific version exists but cannot be read, this returns
+    /// `FindSdkError::MalformedValue`; `SdkVersion::Any` skips such keys and carries on searching.
+    pub fn find(&self) -> Result<Option<SdkInfo>, FindSdkError> {
+        Ok(self.find_detailed()?.map(|(_, info)| info))
+    }
+
+    /// Like `find`, but also returns the version that matched.
+    ///
+    /// The returned version is never `SdkVersion::Any`; if that was searched for, this is the
+    /// version it matched, e.g. `SdkVersion::Env` if the installation was specified by environment
+    /// variables.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error in the same cases as `find`.
+    pub fn find_detailed(&self) -> Result<Option<(SdkVersion, SdkInfo)>, FindSdkError> {
+        self.find_version(self.version)
+    }
+
+    /// Like `find`, but also returns a report of each version that was searched for, where it was
+    /// looked for, and what was found.
+    ///
+    /// This is meant for diagnosing why an installation was not found. The report covers every
+    /// version searched for up to and including the one that was found, or that caused an error.
+    pub fn find_with_report(&self) -> (Result<Option<SdkInfo>, FindSdkError>, SearchReport) {
+        let mut report = SearchReport::default();
+        let mut tolerance = Tolerance::default();
+        for v in &self.versions(self.version) {
+            let skipped = self.skips(*v);
+            let res = self.find_version_unchecked(*v);
+            let (outcome, res) = match res {
+                Ok(None) if skipped => (SearchOutcome::Skipped, Ok(None)),
+                Ok(None) => (SearchOutcome::NotFound, Ok(None)),
+                Ok(Some(ref info)) if self.folder_checks.require_existing && !info.is_present() => {
+                    let folder = info.installation_folder.clone();
+                    (SearchOutcome::FolderMissing { folder }, Ok(None))
+                }
+                Ok(Some(ref info)) if self.folder_checks.require_headers && !info.has_headers() => {
+                    let folder = info.installation_folder.clone();
+                    (SearchOutcome::HeadersMissing { folder }, Ok(None))
+                }
+                Ok(Some(info)) => {
+                    let info = self.canonicalized(info);
+                    let folder = info.installation_folder.clone();
+                    (SearchOutcome::Found { folder }, Ok(Some(info)))
+                }
+                Err(FindSdkError::MalformedValue { key }) => (
+                    SearchOutcome::Malformed { key: key.clone() },
+                    Err(FindSdkError::MalformedValue { key }),
+                ),
+                Err(e) => (SearchOutcome::Failed, Err(e)),
+            };
+            report.steps.push(SearchStep {
+                version: *v,
+                locations: if skipped {
+                    Vec::new()
+                } else {
+                    self.locations(*v)
+                },
+                outcome,
+            });
+            let res = if self.version == SdkVersion::Any && !skipped {
+                tolerance.check(res)
+            } else {
+                res
+            };
+            match res {
+                Ok(None) => (),
+                res => return (res, report),
+            }
+        }
+        (tolerance.finish().map(|()| None), report)
+    }
+
+    /// Returns installation information for every Windows SDK installation matching these
+    /// options.
+    ///
+    /// If the version to search for is `SdkVersion::Any`, installations are listed in the order
+    /// that it searches them, including any preference given by `FIND_WINSDK_PREFER`, so the
+    /// first entry (if any) is the one that `find` would return. An installation that is found
+    /// more than once, e.g. via both environment variables and the registry, is only listed once.
+    ///
+    /// Registry keys that exist but cannot be read are skipped, just as `SdkVersion::Any` skips
+    /// them; use `find` with a specific version to see why a key could not be read. If access is
+    /// denied to the keys of every version searched, that error is returned instead.
+    ///
+    /// With the `vs-setup` feature, Windows 10 SDK builds installed by the Visual Studio Installer
+    /// are also listed, after all other installations, if they are not found otherwise. They are
+    /// filtered by these options just as registered installations are.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error in the same cases as `find`, except that unreadable registry keys are
+    /// skipped as described above.
+    pub fn find_all(&self) -> Result<Vec<SdkInfo>, FindSdkError> {
+        self.iter().collect()
+    }
+
+    /// Searches for each of the given versions in turn, ignoring the version set by `version`.
+    ///
+    /// The result has an entry for each version, in the given order, holding what `find` returns
+    /// for it.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first error that `find` returns for any of the versions.
+    pub fn find_versions(
+        &self,
+        versions: &[SdkVersion],
+    ) -> Result<Vec<(SdkVersion, Option<SdkInfo>)>, FindSdkError> {
+        versions
+            .iter()
+            .map(|v| Ok((*v, self.find_version(*v)?.map(|(_, info)| info))))
+            .collect()
+    }
+
+    /// Returns installation information for each Windows 10 SDK build that is installed, newest
+    /// first, ignoring the version set by `version`.
+    ///
+    /// This is `SdkInfo::find_all_v10` using these options to find the Windows 10 SDK. Preview
+    /// builds are only listed if `include_preview` is set.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the search for the Windows 10 SDK fails, as for `find`.
+    pub fn find_all_v10(&self) -> Result<Vec<SdkInfo>, FindSdkError> {
+        let Some((_, info)) = self.find_version(SdkVersion::V10_0)? else {
+            return Ok(Vec::new());
+        };
+        let builds = info.build_numbers().unwrap_or_default();
+        if builds.is_empty() {
+            return Ok(vec![info]);
+        }
+        Ok(builds
+            .iter()
+            .filter(|build| self.include_preview || !is_preview_build(build))
+            .map(|build| SdkInfo {
+                product_version: trim_zero_revision(build),
+                ..info.clone()
+            })
+            .collect())
+    }
+
+    /// Like `find`, but searches for each of the given versions in turn, instead of the version set
+    /// by `version`, and returns the first installation found.
+    ///
+    /// This is a search for `SdkVersion::Any` with a different search order, e.g. to prefer v8.1
+    /// over v10.0, or to search environment variables only after the registry by putting
+    /// `SdkVersion::Env` later in `order`. Registry keys that cannot be read are skipped as for
+    /// `SdkVersion::Any`, and `min_version` and `use_env` still apply, but `exclude` and
+    /// `FIND_WINSDK_PREFER` are ignored, since `order` already says what to search for.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error in the same cases as a search for `SdkVersion::Any`.
+    pub fn find_in_order(&self, order: &[SdkVersion]) -> Result<Option<SdkInfo>, FindSdkError> {
+        Ok(self.find_first(order)?.map(|(_, info)| info))
+    }
+
+    #[must_use]
+    /// Returns an iterator over every Windows SDK installation matching these options.
+    ///
+    /// This yields the same installations as `find_all`, in the same order, but only searches for
+    /// each version as the iterator advances, so e.g. `find` on the iterator stops searching once
+    /// a suitable installation is found. After an error is yielded, the iterator is exhausted.
+    pub fn iter(&self) -> SdkIter {
+        SdkIter {
+            versions: self.versions(self.version).into_iter(),
+            search: self.clone(),
+            found: Vec::new(),
+            tolerance: Some(Tolerance::default()),
+            #[cfg(feature = "vs-setup")]
+            vs_setup_builds: Vec::new().into_iter(),
+        }
+    }
+
+    fn find_version(
+        &self,
+        version: SdkVersion,
+    ) -> Result<Option<(SdkVersion, SdkInfo)>, FindSdkError> {
+        if version == SdkVersion::Any {
+            return self.find_first(&self.versions(SdkVersion::Any));
+        }
+        let res = self.find_version_unchecked(version)?;
+        Ok(res
+            .filter(|info| self.passes_folder_checks(info))
+            .map(|info| (version, self.canonicalized(info))))
+    }
+
+    /// Returns whether an installation passes the checks set by `require_existing_folder` and
+    /// `require_headers`.
+    fn passes_folder_checks(&self, info: &SdkInfo) -> bool {
+        (!self.folder_checks.require_existing || info.is_present())
+            && (!self.folder_checks.require_headers || info.has_headers())
+    }
+
+    #[cfg(feature = "vs-setup")]
+    /// Returns the Windows 10 SDK builds installed by the Visual Studio Installer that these
+    /// options accept, newest first.
+    ///
+    /// Each build is looked for in the folders set by `with_roots`, then in the folder recorded by
+    /// `KitsRoot10` under `Windows Kits\Installed Roots` (or the default Windows 10 SDK folder, if
+    /// `filesystem_only` is set). Builds are then filtered as registered installations are.
+    fn vs_setup_builds(&self) -> Result<Vec<SdkInfo>, FindSdkError> {
+        let mut folders: Vec<PathBuf> = self
+            .roots
+            .iter()
+            .map(|root| normalize_folder(root.clone()))
+            .collect();
+        if self.filesystem_only {
+            folders.push(probe::v10_folder());
+        } else if let Some(root) = registry::kits_root10(self.registry_hive, self.registry_view)? {
+            folders.push(normalize_folder(root));
+        }
+        Ok(vs_setup::find_v10_builds(&folders)
+            .into_iter()
+            .filter(|info| self.accepts_v10_build(info))
+            .map(|info| self.canonicalized(info))
+            .collect())
+    }
+
+    #[cfg(feature = "vs-setup")]
+    /// Returns whether a Windows 10 SDK build found outside the normal search matches these
+    /// options.
+    fn accepts_v10_build(&self, info: &SdkInfo) -> bool {
+        (self.include_preview || !is_preview_build(&info.product_version))
+            && self.flavor.allows(info.flavor)
+            && self.meets_min_version(info)
+            && self.passes_folder_checks(info)
+    }
+
+    /// Canonicalizes the installation folder of `info` if `canonicalize_paths` is set and the
+    /// folder can be canonicalized.
+    fn canonicalized(&self, mut info: SdkInfo) -> SdkInfo {
+        if self.folder_checks.canonicalize {
+            if let Ok(folder) = fs::canonicalize(&info.installation_folder) {
+                info.installation_folder = folder;
+            }
+        }
+        info
+    }
+
+    /// Returns the first installation found by searching for each version in turn, along with
+    /// the version that matched, tolerating errors as `SdkVersion::Any` does.
+    fn find_first(
+        &self,
+        order: &[SdkVersion],
+    ) -> Result<Option<(SdkVersion, SdkInfo)>, FindSdkError> {
+        let mut tolerance = Tolerance::default();
+        for v in order {
+            if self.skips(*v) {
+                continue;
+            }
+            if let Some(found) = tolerance.check(self.find_version(*v))? {
+                return Ok(Some(found));
+            }
+        }
+        tolerance.finish().map(|()| None)
+    }
+
+    /// Searches for a single version, which must not be `SdkVersion::Any`.
+    fn find_version_unchecked(&self, version: SdkVersion) -> Result<Option<SdkInfo>, FindSdkError> {
+        if version > SdkVersion::Env && version >= self.min_version {
+            let mut found = self
+                .roots
+                .iter()
+                .filter_map(|root| probe::at_root(root, self.include_preview));
+            if let Some(info) = found
+                .find(|info| info.release() == Some(version) && self.flavor.allows(info.flavor))
+            {
+                return Ok(Some(info));
+            }
+        }
+        match version {
+            SdkVersion::Any => Ok(None),
+            SdkVersion::Env => {
+                if !self.env.enabled {
+                    return Ok(None);
+                }
+                let info = match self.query_env()? {
+                    Some(info) if self.env.enrich => self.enrich(info)?,
+                    Some(info) => info,
+                    None => return Ok(None),
+                };
+                Ok(Some(info).filter(|info| self.meets_min_version(info)))
+            }
+            v if v < self.min_version => Ok(None),
+            v if self.filesystem_only => {
+                Ok(
+                    probe::find(v, self.flavor.preferred(), self.include_preview)
+                        .filter(|info| self.flavor.allows(info.flavor)),
+                )
+            }
+            SdkVersion::V10_0 => {
+                let info = match self.query_reg(V10_0_REG_KEY)? {
+                    None => self.query_installed_roots()?,
+                    info => info,
+                };
+                Ok(info.map(|info| self.with_installed_roots_name(info)))
+            }
+            v => match registry_keys(v) {
+                Some(keys) => self.find_double_release(keys),
+                None => Ok(None),
+            },
+        }
+    }
+
+    /// Returns the versions to search for in turn when searching for `version`.
+    fn versions(&self, version: SdkVersion) -> Vec<SdkVersion> {
+        if version != SdkVersion::Any {
+            return vec![version];
+        }
+        let preferred = if self.env.use_prefer_var {
+            env::var_os(PREFER_ENV_VAR).and_then(|value| parse_preference(&value))
+        } else {
+            None
+        };
+        let mut order = any_search_order(preferred);
+        order.retain(|v| !self.exclude.contains(v));
+        order
+    }
+
+    /// Returns whether these options rule out finding a single version without searching for it.
+    fn skips(&self, version: SdkVersion) -> bool {
+        match version {
+            SdkVersion::Env => !self.env.enabled,
+            v => v < self.min_version,
+        }
+    }
+
+    /// Returns the places that a search for a single version consults, in order.
+    fn locations(&self, version: SdkVersion) -> Vec<String> {
+        let mut locations: Vec<String> = Vec::new();
+        if version > SdkVersion::Env {
+            locations.extend(self.roots.iter().map(|root| root.display().to_string()));
+        }
+        let keys = match version {
+            SdkVersion::Any => return locations,
+            SdkVersion::Env => {
+                locations.push(format!("%{}%", self.env.dir_var));
+                locations.push(format!("%{}%", self.env.version_var));
+                return locations;
+            }
+            v if self.filesystem_only => {
+                let mut folders = probe::default_folders(v, self.flavor.preferred());
+                if self.flavor.is_exclusive() {
+                    folders.truncate(1);
+                }
+                locations.extend(folders.iter().map(|folder| folder.display().to_string()));
+                return locations;
+            }
+            SdkVersion::V10_0 => vec![V10_0_REG_KEY, registry::INSTALLED_ROOTS_REG_KEY],
+            v => {
+                let mut keys = match (registry_keys(v), self.flavor.preferred()) {
+                    (Some((with_a, standard)), Flavor::WithA) => vec![with_a, standard],
+                    (Some((with_a, standard)), Flavor::Standard) => vec![standard, with_a],
+                    (None, _) => Vec::new(),
+                };
+                if self.flavor.is_exclusive() {
+                    keys.truncate(1);
+                }
+                keys
+            }
+        };
+        for key in keys {
+            for hive in registry::hive_names(self.registry_hive) {
+                locations.push(format!("{hive}\\{key}"));
+            }
+        }
+        locations
+    }
+
+    /// Fills in details of an installation specified by environment variables from a registered
+    /// installation in the same folder, if there is one.
+    ///
+    /// This is best-effort, so registry keys that cannot be read are skipped.
+    fn enrich(&self, info: SdkInfo) -> Result<SdkInfo, FindSdkError> {
+        let mut tolerance = Tolerance::default();
+        for v in SdkVersion::all_concrete() {
+            if let Some(registered) = tolerance.check(self.find_version_unchecked(*v))? {
+                if same_folder(&info.installation_folder, &registered.installation_folder) {
+                    return Ok(merge_env_info(info, registered));
+                }
+            }
+        }
+        Ok(info)
+    }
+
+    /// Returns whether an installation of unknown release is at least as new as `min_version`.
+    fn meets_min_version(&self, info: &SdkInfo) -> bool {
+        self.min_version <= SdkVersion::Env
+            || info
+                .version()
+                .and_then(|v| SdkVersion::from_version(&v))
+                .is_some_and(|v| v >= self.min_version)
+    }
+
+    /// Searches for a release that comes in two flavours, given its `A` and standard keys.
+    fn find_double_release(&self, keys: (&str, &str)) -> Result<Option<SdkInfo>, FindSdkError> {
+        let (first, second) = match self.flavor.preferred() {
+            Flavor::WithA => ((keys.0, Flavor::WithA), (keys.1, Flavor::Standard)),
+            Flavor::Standard => ((keys.1, Flavor::Standard), (keys.0, Flavor::WithA)),
+        };
+        let query = |(key, flavor)| {
+            self.query_double_release_key(key).map(|res| {
+                res.map(|info| SdkInfo {
+                    flavor: Some(flavor),
+                    ..info
+                })
+            })
+        };
+        if self.flavor.is_exclusive() {
+            return query(first);
+        }
+        // If the preferred flavour's key is malformed, the other flavour might still be usable.
+        match query(first) {
+            Ok(None) => query(second),
+            Err(e @ FindSdkError::MalformedValue { .. }) => match query(second) {
+                Ok(None) => Err(e),
+                res => res,
+            },
+            res => res,
+        }
+    }
+
+    /// Returns installation information for a Windows SDK from environment variables, if present.
+    ///
+    /// Environment variable names are case-insensitive on Windows, so e.g. `WINDOWSSDKDIR` is
+    /// also read in place of `WindowsSdkDir`.
+    fn query_env(&self) -> Result<Option<SdkInfo>, FindSdkError> {
+        self.query_env_with(|name| env::var_os(name))
+    }
+
+    /// Like `query_env`, but reads environment variables using `var` instead of from the process
+    /// environment.
+    ///
+    /// Whitespace around the values of the variables is ignored, since some build environments
+    /// add it when setting them.
+    fn query_env_with<F>(&self, var: F) -> Result<Option<SdkInfo>, FindSdkError>
+    where
+        F: Fn(&str) -> Option<OsString>,
+    {
+        let var = |name: &str| var(name).map(trim_env_value);
+        let (Some(install_dir), Some(version)) =
+            (var(&self.env.dir_var), var(&self.env.version_var))
+        else {
+            trace!(
+                "`{}` and `{}` are not both set",
+                self.env.dir_var,
+                self.env.version_var
+            );
+            return Ok(None);
+        };
+        let ver = parse_env_version(&self.env.version_var, version)?;
+        debug!(
+            "environment variables specify version `{}` at `{}`",
+            ver,
+            Path::new(&install_dir).display()
+        );
+        Ok(Some(SdkInfo {
+            installation_folder: normalize_folder(PathBuf::from(install_dir)),
+            product_name: None,
+            product_version: ver,
+            bin_dir_from_env: var("WindowsSdkVerBinPath").map(PathBuf::from),
+            flavor: None,
+            raw_values: BTreeMap::new(),
+            registry_last_write: None,
+        }))
+    }
+
+    /// Returns installation information for the newest Windows 10 SDK build registered under
+    /// `Windows Kits\Installed Roots`, skipping preview builds unless `include_preview` is set.
+    ///
+    /// This is used when the `Microsoft SDKs\Windows\v10.0` key is absent, which can happen even
+    /// though the SDK is installed.
+    fn query_installed_roots(&self) -> Result<Option<SdkInfo>, FindSdkError> {
+        let Some(root) = registry::kits_root10(self.registry_hive, self.registry_view)? else {
+            return Ok(None);
+        };
+        let builds = registry::installed_v10_builds(self.registry_hive, self.registry_view)?;
+        let newest = builds
+            .into_iter()
+            .find(|build| self.include_preview || !is_preview_build(build));
+        Ok(newest.map(|build| SdkInfo {
+            installation_folder: normalize_folder(root),
+            product_name: None,
+            product_version: trim_zero_revision(&build),
+            bin_dir_from_env: None,
+            flavor: None,
+            raw_values: BTreeMap::new(),
+            registry_last_write: None,
+        }))
+    }
+
+    /// Reads a double-release registry key, falling back to its component subkeys if it is
+    /// malformed.
+    fn query_double_release_key(&self, key: &str) -> Result<Option<SdkInfo>, FindSdkError> {
+        let err = match self.query_reg(key) {
+            Err(e @ FindSdkError::MalformedValue { .. }) => e,
+            res => return res,
+        };
+        for subkey in &self.component_subkeys {
+            match self.query_reg(&format!("{key}\\{subkey}")) {
+                Ok(Some(info)) => {
+                    return Ok(Some(SdkInfo {
+                        installation_folder: component_root(info.installation_folder),
+                        ..info
+                    }));
+                }
+                Ok(None) | Err(FindSdkError::MalformedValue { .. }) => (),
+                Err(e) => return Err(e),
+            }
+        }
+        Err(err)
+    }
+
+    /// Fills in the product name of a Windows 10 SDK installation from its build's subkey of
+    /// `Windows Kits\Installed Roots`, if it has none.
+    ///
+    /// The `v10.0` key often has no `ProductName` value. Errors reading the subkey are ignored,
+    /// since the name is only informative.
+    fn with_installed_roots_name(&self, info: SdkInfo) -> SdkInfo {
+        if info.product_name.is_some() {
+            return info;
+        }
+        let subkey = format!(
+            "{}\\{}",
+            registry::INSTALLED_ROOTS_REG_KEY,
+            info.build_folder_name()
+        );
+        let product_name = match registry::open_key(&subkey, self.registry_hive, self.registry_view)
+        {
+            Ok(Some(key)) => registry::get_string(&key, "ProductName").unwrap_or(None),
+            _ => None,
+        };
+        SdkInfo {
+            product_name,
+            ..info
+        }
+    }
+
+    fn query_reg(&self, subkey: &str) -> Result<Option<SdkInfo>, FindSdkError> {
+        let mut attempts = 0;
+        let key = loop {
+            match registry::open_key(subkey, self.registry_hive, self.registry_view) {
+                Err(FindSdkError::Registry(ref e))
+                    if attempts < self.retries
+                        && !NEVER_RETRIED_ERROR_KINDS.contains(&e.kind()) =>
+                {
+                    attempts += 1;
+                    debug!("retrying registry key `{subkey}` after error: {e}");
+                    thread::sleep(RETRY_DELAY);
+                }
+                res => break res?,
+            }
+        };
+        match key {
+            Some(key) => decode_key(&key, subkey).map(Some),
+            None => Ok(None),
+        }
+    }
+}
+
+/// Returns the `A` and standard flavours' registry keys for a release.
+///
+/// The Windows 10 SDK only comes in one flavour, so both keys are the same for it.
+pub fn registry_keys(version: SdkVersion) -> Option<(&'static str, &'static str)> {
+    match version {
+        SdkVersion::Any | SdkVersion::Env => None,
+        SdkVersion::V10_0 => Some((V10_0_REG_KEY, V10_0_REG_KEY)),
+        SdkVersion::V8_1 => Some((V8_1A_REG_KEY, V8_1_REG_KEY)),
+        SdkVersion::V8_0 => Some((V8_0A_REG_KEY, V8_0_REG_KEY)),
+        SdkVersion::V7_1 => Some((V7_1A_REG_KEY, V7_1_REG_KEY)),
+        SdkVersion::V7_0 => Some((V7_0A_REG_KEY, V7_0_REG_KEY)),
+        SdkVersion::V6_1 => Some((V6_1A_REG_KEY, V6_1_REG_KEY)),
+        SdkVersion::V6_0 => Some((V6_0A_REG_KEY, V6_0_REG_KEY)),
+    }
+}
+
+/// Returns every registry key searched for a release, with the `A` flavour's key first.
+pub fn all_registry_keys(version: SdkVersion) -> &'static [&'static str] {
+    match version {
+        SdkVersion::Any | SdkVersion::Env => &[],
+        SdkVersion::V10_0 => &[V10_0_REG_KEY],
+        SdkVersion::V8_1 => &[V8_1A_REG_KEY, V8_1_REG_KEY],
+        SdkVersion::V8_0 => &[V8_0A_REG_KEY, V8_0_REG_KEY],
+        SdkVersion::V7_1 => &[V7_1A_REG_KEY, V7_1_REG_KEY],
+        SdkVersion::V7_0 => &[V7_0A_REG_KEY, V7_0_REG_KEY],
+        SdkVersion::V6_1 => &[V6_1A_REG_KEY, V6_1_REG_KEY],
+        SdkVersion::V6_0 => &[V6_0A_REG_KEY, V6_0_REG_KEY],
+    }
+}
+
+/// Parses the value of `FIND_WINSDK_PREFER`, returning `None` if it does not name a version.
+///
+/// `any` is accepted, but expresses no preference. Other values are ignored with a warning.
+fn parse_preference(value: &OsStr) -> Option<SdkVersion> {
+    match value.to_str().map(|value| value.trim().parse()) {
+        Some(Ok(SdkVersion::Any)) => None,
+        Some(Ok(v)) => Some(v),
+        _ => {
+            warn!(
+                "ignoring `{PREFER_ENV_VAR}`, which does not name a Windows SDK version: `{}`",
+                value.to_string_lossy()
+            );
+            None
+        }
+    }
+}
+
+/// Returns the order in which `SdkVersion::Any` searches versions, given a version to search
+/// for first.
+fn any_search_order(preferred: Option<SdkVersion>) -> Vec<SdkVersion> {
+    let mut order: Vec<SdkVersion> = preferred.into_iter().collect();
+    order.extend(ANY_SEARCH_ORDER.iter().filter(|v| Some(**v) != preferred));
+    order
+}
+
+/// Parses the value of the `WindowsSdkVersion` environment variable, or the one named `name` in its
+/// place.
+///
+/// Developer command prompts set this to e.g. `10.0.17763.0\`, but the registry records the same
+/// version as `10.0.17763`, so any trailing separator and a zero fourth component are removed.
+/// The build number itself is always kept, so `include_dir` and `lib_dir` use the build that the
+/// prompt selected rather than the newest one. Other values, such as a bare `10.0` or `8.1`, are
+/// kept as they are.
+fn parse_env_version(name: &str, version: OsString) -> Result<String, FindSdkError> {
+    let version = version
+        .into_string()
+        .map_err(|_| FindSdkError::InvalidEnvVar {
+            name: name.to_owned(),
+        })?;
+    let version = version.trim_end_matches(['\\', '/']);
+    Ok(trim_zero_revision(version))
+}
+
+/// Removes whitespace from around the value of an environment variable.
+///
+/// Values that are not valid Unicode are returned unchanged.
+fn trim_env_value(value: OsString) -> OsString {
+    match value.into_string() {
+        Ok(value) => OsString::from(value.trim()),
+        Err(value) => value,
+    }
+}
+
+/// Removes a zero fourth component from a version number, e.g. `10.0.17763.0` becomes
+/// `10.0.17763`, to match how the registry records Windows 10 SDK builds.
+pub fn trim_zero_revision(version: &str) -> String {
+    let components: Vec<&str> = version.split('.').collect();
+    if components.len() == 4 && components[3] == "0" {
+        components[..3].join(".")
+    } else {
+        version.to_owned()
+    }
+}
+
+/// Returns whether a Windows 10 SDK build folder name, e.g. `10.0.25336.1000`, belongs to a
+/// preview build, as described for `SdkSearch::include_preview`.
+pub fn is_preview_build(build: &str) -> bool {
+    build
+        .parse::<Version>()
+        .is_ok_and(|version| version.revision != 0)
+}
+
+/// Combines installation information from environment variables with that of the matching
+/// registered installation.
+pub fn merge_env_info(env: SdkInfo, registered: SdkInfo) -> SdkInfo {
+    let refines = registered.product_version == env.product_version
+        || registered
+            .product_version
+            .starts_with(&format!("{}.", env.product_version));
+    SdkInfo {
+        installation_folder: env.installation_folder,
+        product_version: if refines {
+            registered.product_version
+        } else {
+            env.product_version
+        },
+        product_name: registered.product_name.or(env.product_name),
+        bin_dir_from_env: env.bin_dir_from_env,
+        ..registered
+    }
+}
+
+/// Returns the installation folder that a component folder belongs to: the nearest folder at or
+/// above it that has an `Include` subfolder, or the component folder itself if there is none.
+fn component_root(folder: PathBuf) -> PathBuf {
+    let root = folder
+        .ancestors()
+        .find(|dir| dir.join("Include").is_dir())
+        .map(Path::to_path_buf);
+    normalize_folder(root.unwrap_or(folder))
+}
+
+/// Returns whether two paths name the same folder, ignoring case and trailing separators.
+pub fn same_folder(a: &Path, b: &Path) -> bool {
+    let normalize = |p: &Path| {
+        p.to_string_lossy()
+            .trim_end_matches(['\\', '/'])
+            .to_lowercase()
+    };
+    normalize(a) == normalize(b)
+}
+
+/// Reads installation information from a registry key.
+///
+/// The key must have non-empty string `InstallationFolder` and `ProductVersion` values, and may
+/// have a string `ProductName` value. If not, the key might not have been deleted correctly; an
+/// empty `InstallationFolder` in particular is left behind by some failed uninstalls.
+fn decode_key(key: &RegistryKey, path: &str) -> Result<SdkInfo, FindSdkError> {
+    let string = |name: &str| match key.value(name) {
+        Some(RegistryValue::String(value)) => Ok(Some(value.clone())),
+        None => Ok(None),
+        Some(_) => Err(name.to_owned()),
+    };
+    let required = |name: &str| {
+        string(name)?
+            .filter(|value| !value.trim().is_empty())
+            .ok_or_else(|| name.to_owned())
+    };
+    let decoded = required("InstallationFolder")
+        .and_then(|folder| Ok((folder, required("ProductVersion")?, string("ProductName")?)));
+    let (folder, product_version, product_name) = decoded.map_err(|name| {
+        debug!("could not decode registry key `{path}`: no valid `{name}` value");
+        FindSdkError::MalformedValue {
+            key: path.to_owned(),
+        }
+    })?;
+    trace!("registry key `{path}` gives version `{product_version}` at `{folder}`");
+    Ok(SdkInfo {
+        installation_folder: normalize_folder(PathBuf::from(folder)),
+        product_name,
+        product_version,
+        bin_dir_from_env: None,
+        flavor: None,
+        raw_values: registry::read_values(key),
+        registry_last_write: key.last_write,
+    })
+}
+
+/// Treats a registry key that cannot be read as if it does not exist.
+///
+/// This is used when searching several versions, so that leftovers from an SDK that was not
+/// uninstalled cleanly do not hide other installations.
+fn skip_malformed<T>(res: Result<Option<T>, FindSdkError>) -> Result<Option<T>, FindSdkError> {
+    match res {
+        Err(FindSdkError::MalformedValue { .. }) => Ok(None),
+        _ => res,
+    }
+}
+
+#[derive(Debug)]
+/// An iterator over Windows SDK installations, created by `SdkSearch::iter` or `SdkInfo::iter`.
+pub struct SdkIter {
+    search: SdkSearch,
+    versions: vec::IntoIter<SdkVersion>,
+    found: Vec<SdkInfo>,
+    /// Errors tolerated so far, or `None` once every version has been searched for.
+    tolerance: Option<Tolerance>,
+    #[cfg(feature = "vs-setup")]
+    vs_setup_builds: vec::IntoIter<SdkInfo>,
+}
+
+impl SdkIter {
+    /// Returns the next installation found by searching for a version, if any.
+    fn next_version(&mut self) -> Option<Result<SdkInfo, FindSdkError>> {
+        let tolerance = self.tolerance.as_mut()?;
+        for v in &mut self.versions {
+            if self.search.version == SdkVersion::Any && self.search.skips(v) {
+                continue;
+            }
+            match tolerance.check(self.search.find_version(v)) {
+                Ok(Some((_, info))) => {
+                    if !self.found.iter().any(|i| i.is_same_installation(&info)) {
+                        self.found.push(info.clone());
+                        return Some(Ok(info));
+                    }
+                }
+                Ok(None) => (),
+                Err(e) => {
+                    self.tolerance = None;
+                    return Some(Err(e));
+                }
+            }
+        }
+        let res = self.tolerance.take()?.finish();
+        #[cfg(feature = "vs-setup")]
+        {
+            let search = &self.search;
+            let v10 = match search.version {
+                SdkVersion::Any => !search.exclude.contains(&SdkVersion::V10_0),
+                v => v == SdkVersion::V10_0,
+            };
+            if res.is_ok() && v10 && SdkVersion::V10_0 >= search.min_version {
+                match search.vs_setup_builds() {
+                    Ok(builds) => self.vs_setup_builds = builds.into_iter(),
+                    Err(e) => return Some(Err(e)),
+                }
+            }
+        }
+        res.err().map(Err)
+    }
+}
+
+impl Iterator for SdkIter {
+    type Item = Result<SdkInfo, FindSdkError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(res) = self.next_version() {
+            return Some(res);
+        }
+        #[cfg(feature = "vs-setup")]
+        {
+            for info in &mut self.vs_setup_builds {
+                if !self.found.iter().any(|i| i.is_same_installation(&info)) {
+                    self.found.push(info.clone());
+                    return Some(Ok(info));
+                }
+            }
+        }
+        None
+    }
+}
+
+#[derive(Debug, Default)]
+/// Tracks the errors tolerated while searching several versions in turn.
+struct Tolerance {
+    /// The last error tolerated, if any.
+    error: Option<FindSdkError>,
+    /// Whether any version was searched without a tolerated error.
+    searched: bool,
+}
+
+impl Tolerance {
+    /// Skips a malformed key, or a registry error of a kind in `TOLERATED_ERROR_KINDS`, for a
+    /// single version.
+    fn check<T>(
+        &mut self,
+        res: Result<Option<T>, FindSdkError>,
+    ) -> Result<Option<T>, FindSdkError> {
+        match skip_malformed(res) {
+            Err(e) => {
+                if is_tolerated(&e) {
+                    self.error = Some(e);
+                    Ok(None)
+                } else {
+                    Err(e)
+                }
+            }
+            res => {
+                self.searched = true;
+                res
+            }
+        }
+    }
+
+    /// Returns the last error tolerated if every version searched failed with one.
+    fn finish(self) -> Result<(), FindSdkError> {
+        match self.error {
+            Some(e) if !self.searched => Err(e),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Returns whether an error is one that a search across several versions tolerates.
+fn is_tolerated(e: &FindSdkError) -> bool {
+    match *e {
+        FindSdkError::Registry(ref e) => TOLERATED_ERROR_KINDS.contains(&e.kind()),
+        _ => false,
+    }
+}
+
+impl Default for SdkSearch {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl IntoIterator for &SdkSearch {
+    type Item = Result<SdkInfo, FindSdkError>;
+    type IntoIter = SdkIter;
+
+    fn into_iter(self) -> SdkIter {
+        self.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        any_search_order, decode_key, is_tolerated, merge_env_info, parse_env_version,
+        parse_preference, same_folder, Tolerance, V10_0_REG_KEY, V7_1_REG_KEY, V8_0_REG_KEY,
+        V8_1A_REG_KEY, V8_1_REG_KEY,
+    };
+    use registry::{self, MockRegistry, RegistryKey, RegistryValue};
+    use std::collections::BTreeMap;
+    use std::ffi::{OsStr, OsString};
+    use std::io;
+    use std::path::PathBuf;
+    #[cfg(target_os = "windows")]
+    use winreg::enums::HKEY_CURRENT_USER;
+    #[cfg(target_os = "windows")]
+    use winreg::RegKey;
+    use {
+        FindSdkError, Flavor, RegistryHive, RegistryView, SdkInfo, SdkSearch, SdkVersion,
+        SearchOutcome,
+    };
+
+    #[test]
+    fn env_version() {
+        let parse = |s: &str| {
+            parse_env_version("WindowsSdkVersion", OsString::from(s)).expect("invalid version")
+        };
+        assert_eq!(parse("10.0.17763.0\\"), "10.0.17763");
+        assert_eq!(parse("10.0.17763.0"), "10.0.17763");
+        assert_eq!(parse("10.0"), "10.0");
+        assert_eq!(parse("10.0\\"), "10.0");
+        assert_eq!(parse("10.0.19041.1\\"), "10.0.19041.1");
+        assert_eq!(parse("8.1"), "8.1");
+        assert_eq!(parse("8.1\\"), "8.1");
+        match parse_env_version("WindowsSdkVersion", invalid_unicode()) {
+            Err(FindSdkError::InvalidEnvVar { ref name }) => assert_eq!(name, "WindowsSdkVersion"),
+            other => panic!(
+                "expected an invalid environment variable error, got {:?}",
+                other
+            ),
+        }
+    }
+
+    #[cfg(target_os = "windows")]
+    /// Returns a string that is not valid Unicode.
+    fn invalid_unicode() -> OsString {
+        use std::os::windows::ffi::OsStringExt;
+        OsString::from_wide(&[0xD800])
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    /// Returns a string that is not valid Unicode.
+    fn invalid_unicode() -> OsString {
+        use std::os::unix::ffi::OsStringExt;
+        OsString::from_vec(vec![0xff])
+    }
+
+    #[test]
+    fn env_var_names() {
+        let mut vars = BTreeMap::new();
+        let _ = vars.insert("MY_SDK_DIR", OsString::from(r"C:\find-winsdk\sdk\"));
+        let _ = vars.insert("MY_SDK_VERSION", OsString::from(r"10.0.19041.0\"));
+        let _ = vars.insert(
+            "WindowsSdkVerBinPath",
+            OsString::from(r"C:\find-winsdk\bin"),
+        );
+        let var = |name: &str| vars.get(name).cloned();
+        let mut search = SdkSearch::new();
+        assert_eq!(search.query_env_with(var).expect("invalid variables"), None);
+        let _ = search.env_var_names("MY_SDK_DIR", "MY_SDK_VERSION");
+        let info = search
+            .query_env_with(var)
+            .expect("invalid variables")
+            .expect("variables were not found");
+        assert_eq!(
+            info.installation_folder(),
+            PathBuf::from(r"C:\find-winsdk\sdk")
+        );
+        assert_eq!(info.product_version(), "10.0.19041");
+        assert_eq!(
+            info.bin_dir_from_env(),
+            Some(PathBuf::from(r"C:\find-winsdk\bin").as_path())
+        );
+        assert_eq!(
+            search.locations(SdkVersion::Env),
+            vec!["%MY_SDK_DIR%", "%MY_SDK_VERSION%"]
+        );
+        let _ = vars.insert("MY_SDK_VERSION", invalid_unicode());
+        let var = |name: &str| vars.get(name).cloned();
+        match search.query_env_with(var) {
+            Err(FindSdkError::InvalidEnvVar { ref name }) => assert_eq!(name, "MY_SDK_VERSION"),
+            other => panic!(
+                "expected an invalid environment variable error, got {:?}",
+                other
+            ),
+        }
+    }
+
+    #[test]
+    fn env_whitespace() {
+        let mut vars = BTreeMap::new();
+        let _ = vars.insert(
+            "WindowsSdkDir",
+            OsString::from("  C:\\find-winsdk\\sdk\\ \t"),
+        );
+        let _ = vars.insert("WindowsSdkVersion", OsString::from(" 10.0.19041.0\\  "));
+        let _ = vars.insert(
+            "WindowsSdkVerBinPath",
+            OsString::from("C:\\find-winsdk\\bin\r\n"),
+        );
+        let info = SdkSearch::new()
+            .query_env_with(|name| vars.get(name).cloned())
+            .expect("invalid variables")
+            .expect("variables were not found");
+        assert_eq!(
+            info.installation_folder(),
+            PathBuf::from(r"C:\find-winsdk\sdk")
+        );
+        assert_eq!(info.product_version(), "10.0.19041");
+        assert_eq!(
+            info.bin_dir_from_env(),
+            Some(PathBuf::from(r"C:\find-winsdk\bin").as_path())
+        );
+    }
+
+    #[test]
+    #[cfg(target_os = "windows")]
+    fn env_version_layout() {
+        use std::env;
+        use std::fs;
+        let root = env::temp_dir().join("find-winsdk-env-version-layout");
+        let create = || -> io::Result<()> {
+            for build in &["10.0.17763.0", "10.0.19041.0"] {
+                fs::create_dir_all(root.join("Include").join(build))?;
+                fs::create_dir_all(root.join("Lib").join(build))?;
+            }
+            Ok(())
+        };
+        let res = create().map(|()| SdkInfo {
+            installation_folder: root.clone(),
+            product_name: None,
+            product_version: parse_env_version(
+                "WindowsSdkVersion",
+                OsString::from("10.0.17763.0\\"),
+            )
+            .expect("invalid version"),
+            bin_dir_from_env: None,
+            flavor: None,
+            raw_values: BTreeMap::new(),
+            registry_last_write: None,
+        });
+        let dirs = res.map(|info| (info.include_dir(), info.lib_dir()));
+        fs::remove_dir_all(&root).expect("could not delete test folder");
+        let (include, lib) = dirs.expect("could not create test folder");
+        assert_eq!(include, root.join(r"Include\10.0.17763.0"));
+        assert_eq!(lib, root.join(r"Lib\10.0.17763.0"));
+    }
+
+    #[test]
+    fn env_enrichment() {
+        let info = |folder: &str, name: Option<&str>, version: &str| SdkInfo {
+            installation_folder: PathBuf::from(folder),
+            product_name: name.map(str::to_owned),
+            product_version: version.to_owned(),
+            bin_dir_from_env: None,
+            flavor: None,
+            raw_values: BTreeMap::new(),
+            registry_last_write: None,
+        };
+        let registered = info(
+            r"C:\Program Files (x86)\Windows Kits\8.1\",
+            Some("Windows Software Development Kit for Windows 8.1"),
+            "8.1.25984",
+        );
+        let env = info(r"c:\program files (x86)\windows kits\8.1", None, "8.1");
+        assert!(same_folder(
+            &env.installation_folder,
+            &registered.installation_folder
+        ));
+        let merged = merge_env_info(env.clone(), registered.clone());
+        assert_eq!(merged.installation_folder(), env.installation_folder());
+        assert_eq!(merged.product_name(), registered.product_name());
+        assert_eq!(merged.product_version(), "8.1.25984");
+        let build = info(
+            r"C:\Program Files (x86)\Windows Kits\10\",
+            None,
+            "10.0.17763",
+        );
+        let latest = info(
+            r"C:\Program Files (x86)\Windows Kits\10\",
+            None,
+            "10.0.19041",
+        );
+        assert_eq!(
+            merge_env_info(build.clone(), latest).product_version(),
+            "10.0.17763"
+        );
+        let named = info(
+            r"C:\Program Files (x86)\Windows Kits\10\",
+            Some("Windows 10 SDK"),
+            "10.0.17763",
+        );
+        let merged = named.merge_env_with(&build);
+        assert_eq!(merged.product_name(), Some("Windows 10 SDK"));
+        assert_eq!(merged.installation_folder(), named.installation_folder());
+        assert_eq!(
+            env.merge_env_with(&registered).product_version(),
+            "8.1.25984"
+        );
+    }
+
+    #[test]
+    fn preference() {
+        use ANY_SEARCH_ORDER;
+        assert_eq!(parse_preference(OsStr::new("v8.1")), Some(SdkVersion::V8_1));
+        assert_eq!(
+            parse_preference(OsStr::new(" 10.0 ")),
+            Some(SdkVersion::V10_0)
+        );
+        assert_eq!(parse_preference(OsStr::new("any")), None);
+        assert_eq!(parse_preference(OsStr::new("v9.9")), None);
+        assert_eq!(parse_preference(OsStr::new("")), None);
+        assert_eq!(any_search_order(None), ANY_SEARCH_ORDER.to_vec());
+        let order = any_search_order(Some(SdkVersion::V8_1));
+        assert_eq!(order.len(), ANY_SEARCH_ORDER.len());
+        assert_eq!(order[0], SdkVersion::V8_1);
+        assert_eq!(order[1..3], [SdkVersion::Env, SdkVersion::V10_0]);
+    }
+
+    #[test]
+    fn tolerated_errors() {
+        let denied = || FindSdkError::Registry(io::Error::from(io::ErrorKind::PermissionDenied));
+        let malformed = || FindSdkError::MalformedValue {
+            key: "key".to_owned(),
+        };
+        assert!(is_tolerated(&denied()));
+        assert!(!is_tolerated(&malformed()));
+        assert!(!is_tolerated(&FindSdkError::Registry(io::Error::from(
+            io::ErrorKind::InvalidData
+        ))));
+
+        let mut tolerance = Tolerance::default();
+        assert!(tolerance.check::<()>(Err(denied())).unwrap().is_none());
+        assert!(tolerance.check::<()>(Err(denied())).unwrap().is_none());
+        match tolerance.finish() {
+            Err(FindSdkError::Registry(ref e)) => {
+                assert_eq!(e.kind(), io::ErrorKind::PermissionDenied);
+            }
+            res => panic!("expected access to be denied, got {:?}", res),
+        }
+
+        let mut tolerance = Tolerance::default();
+        assert!(tolerance.check::<()>(Err(denied())).unwrap().is_none());
+        assert!(tolerance.check::<()>(Err(malformed())).unwrap().is_none());
+        assert!(tolerance.finish().is_ok());
+
+        let mut tolerance = Tolerance::default();
+        let other = Err(FindSdkError::Registry(io::Error::from(
+            io::ErrorKind::Other,
+        )));
+        assert!(tolerance.check::<()>(other).is_err());
+        assert!(Tolerance::default().finish().is_ok());
+    }
+
+    #[test]
+    fn malformed_key() {
+        let path = r"Software\find-winsdk\tests\malformed_key";
+        let mut key = RegistryKey::default();
+        let _ = key.values.insert(
+            "InstallationFolder".to_owned(),
+            RegistryValue::String(r"C:\Program Files (x86)\Windows Kits\10\".to_owned()),
+        );
+        let missing_version = decode_key(&key, path);
+        let _ = key
+            .values
+            .insert("ProductVersion".to_owned(), RegistryValue::Number(10));
+        let numeric_version = decode_key(&key, path);
+        let _ = key.values.insert(
+            "ProductVersion".to_owned(),
+            RegistryValue::String("10.0.17763".to_owned()),
+        );
+        let mut results = vec![missing_version, numeric_version];
+        for folder in &["", "  "] {
+            let _ = key.values.insert(
+                "InstallationFolder".to_owned(),
+                RegistryValue::String((*folder).to_owned()),
+            );
+            results.push(decode_key(&key, path));
+        }
+        for res in results {
+            match res {
+                Err(FindSdkError::MalformedValue { ref key }) => assert_eq!(key, path),
+                other => panic!("expected a malformed value error, got {:?}", other),
+            }
+        }
+    }
+
+    #[test]
+    #[cfg(target_os = "windows")]
+    fn component_subkeys() {
+        use std::env;
+        use std::fs;
+        let path = r"Software\find-winsdk\tests\component_subkeys";
+        let root = env::temp_dir().join("find-winsdk-component-subkeys");
+        let tools = root.join(r"bin\NETFX 4.0 Tools");
+        let hkcu = RegKey::predef(HKEY_CURRENT_USER);
+        let (key, _) = hkcu
+            .create_subkey(path)
+            .expect("could not create test registry key");
+        let write = || -> io::Result<()> {
+            fs::create_dir_all(root.join("Include"))?;
+            fs::create_dir_all(&tools)?;
+            key.set_value("ProductVersion", &"7.1.7600.0.30514")?;
+            let (component, _) = key.create_subkey("WinSDK-NetFx40Tools")?;
+            component.set_value("InstallationFolder", &tools.to_string_lossy().into_owned())?;
+            component.set_value("ProductVersion", &"7.1.7600.0.30514")
+        };
+        let mut search = SdkSearch::new();
+        let _ = search.registry_hive(RegistryHive::CurrentUser);
+        let res = write().map(|()| {
+            let found = search.query_double_release_key(path);
+            let _ = search.component_subkeys(&[]);
+            (found, search.query_double_release_key(path))
+        });
+        hkcu.delete_subkey_all(path)
+            .expect("could not delete test registry key");
+        let _ = fs::remove_dir_all(&root);
+        let (found, disabled) = res.expect("could not write test registry values");
+        let info = found
+            .expect("could not read component subkey")
+            .expect("component subkey was not found");
+        assert_eq!(info.installation_folder(), root.as_path());
+        assert_eq!(info.product_version(), "7.1.7600.0.30514");
+        match disabled {
+            Err(FindSdkError::MalformedValue { ref key }) => assert_eq!(key, path),
+            other => panic!("expected a malformed value error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    #[cfg(target_os = "windows")]
+    fn raw_values() {
+        let path = r"Software\find-winsdk\tests\raw_values";
+        let hkcu = RegKey::predef(HKEY_CURRENT_USER);
+        let (key, _) = hkcu
+            .create_subkey(path)
+            .expect("could not create test registry key");
+        let write = || -> io::Result<()> {
+            key.set_value(
+                "InstallationFolder",
+                &r"C:\Program Files (x86)\Windows Kits\10\",
+            )?;
+            key.set_value("ProductVersion", &"10.0.17763")?;
+            key.set_value("ManifestEnabled", &1u32)
+        };
+        let res = write().map(|()| {
+            let key = registry::open_key(path, RegistryHive::CurrentUser, RegistryView::Any)?
+                .expect("test registry key does not exist");
+            decode_key(&key, path)
+        });
+        hkcu.delete_subkey_all(path)
+            .expect("could not delete test registry key");
+        let info = res
+            .expect("could not write test registry values")
+            .expect("could not decode test registry key");
+        let raw = info.raw_values();
+        assert_eq!(
+            raw.get("ProductVersion").map(|s| &s[..]),
+            Some("10.0.17763")
+        );
+        assert_eq!(raw.get("ManifestEnabled").map(|s| &s[..]), Some("1"));
+        assert_eq!(raw.len(), 3);
+        assert_eq!(info.manifest_enabled(), Some(true));
+    }
+
+    #[test]
+    fn find_all() {
+        let mut search = SdkSearch::new();
+        let _ = search.use_env(false);
+        let all = search
+            .find_all()
+            .expect("could not retrieve Windows SDK info from registry");
+        let first = search
+            .find()
+            .expect("could not retrieve Windows SDK info from registry");
+        assert_eq!(all.first(), first.as_ref());
+    }
+
+    #[test]
+    fn installed_roots_name() {
+        let info = SdkInfo {
+            installation_folder: PathBuf::from(r"C:\find-winsdk\does\not\exist"),
+            product_name: Some("Windows Software Development Kit".to_owned()),
+            product_version: "10.0.99999".to_owned(),
+            bin_dir_from_env: None,
+            flavor: None,
+            raw_values: BTreeMap::new(),
+            registry_last_write: None,
+        };
+        let search = SdkSearch::new();
+        assert_eq!(search.with_installed_roots_name(info.clone()), info);
+        let unnamed = SdkInfo {
+            product_name: None,
+            ..info
+        };
+        assert_eq!(search.with_installed_roots_name(unnamed.clone()), unnamed);
+    }
+
+    #[test]
+    fn find_versions() {
+        let search = SdkSearch::new();
+        let versions = [SdkVersion::V8_1, SdkVersion::V10_0];
+        let found = search
+            .find_versions(&versions)
+            .expect("could not retrieve Windows SDK info from registry");
+        assert_eq!(found.len(), 2);
+        for (&(v, ref info), expected) in found.iter().zip(&versions) {
+            assert_eq!(v, *expected);
+            let mut single = search.clone();
+            let _ = single.version(v);
+            assert_eq!(
+                *info,
+                single
+                    .find()
+                    .expect("could not retrieve Windows SDK info from registry")
+            );
+        }
+        assert!(search
+            .find_versions(&[])
+            .is_ok_and(|found| found.is_empty()));
+    }
+
+    #[test]
+    fn iter() {
+        let all = SdkSearch::new()
+            .find_all()
+            .expect("could not retrieve Windows SDK info from registry");
+        let mut iter = SdkSearch::new().iter();
+        assert_eq!(
+            iter.next()
+                .map(|res| res.expect("could not retrieve Windows SDK info")),
+            all.first().cloned()
+        );
+        let rest: Result<Vec<_>, _> = iter.collect();
+        assert_eq!(
+            rest.expect("could not retrieve Windows SDK info from registry"),
+            all.iter().skip(1).cloned().collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    #[cfg(target_os = "windows")]
+    fn find_detailed() {
+        let (version, info) = SdkSearch::new()
+            .find_detailed()
+            .expect("could not retrieve Windows SDK info from registry")
+            .expect("Windows SDK is not installed");
+        assert_ne!(version, SdkVersion::Any);
+        let again = SdkSearch::new()
+            .version(version)
+            .find()
+            .expect("could not retrieve Windows SDK info from registry");
+        assert_eq!(again, Some(info));
+    }
+
+    #[test]
+    #[cfg(target_os = "windows")]
+    fn filesystem_only() {
+        let info = SdkSearch::new()
+            .version(SdkVersion::V10_0)
+            .filesystem_only(true)
+            .find()
+            .expect("filesystem-only search failed")
+            .expect("Windows 10 SDK is not installed in its default location");
+        assert!(info.is_present());
+        assert!(info.include_dir().join("um").is_dir());
+    }
+
+    #[test]
+    #[cfg(target_os = "windows")]
+    fn with_roots() {
+        use std::env;
+        use std::fs;
+        let base = env::temp_dir().join("find-winsdk-with-roots");
+        let v10 = base.join("10");
+        let v7_1a = base.join("v7.1A");
+        let unknown = base.join("unknown");
+        let create = || -> io::Result<()> {
+            fs::create_dir_all(v10.join(r"Include\10.0.17763.0"))?;
+            fs::create_dir_all(v10.join(r"Include\10.0.19041.0"))?;
+            fs::create_dir_all(v7_1a.join("Include"))?;
+            fs::create_dir_all(unknown.join("Include"))
+        };
+        let res = create().map(|()| {
+            let mut search = SdkSearch::new();
+            let _ = search.use_env(false).filesystem_only(true).with_roots(&[
+                unknown.clone(),
+                v7_1a.clone(),
+                v10.clone(),
+            ]);
+            let find = |version| {
+                search
+                    .clone()
+                    .version(version)
+                    .find()
+                    .expect("could not search test folders")
+            };
+            (find(SdkVersion::V10_0), find(SdkVersion::V7_1))
+        });
+        fs::remove_dir_all(&base).expect("could not delete test folders");
+        let (v10_info, v7_1_info) = res.expect("could not create test folders");
+        let v10_info = v10_info.expect("could not find Windows 10 SDK in test folder");
+        assert_eq!(v10_info.installation_folder(), v10.as_path());
+        assert_eq!(v10_info.product_version(), "10.0.19041");
+        let v7_1_info = v7_1_info.expect("could not find Windows 7.1 SDK in test folder");
+        assert_eq!(v7_1_info.installation_folder(), v7_1a.as_path());
+        assert_eq!(v7_1_info.flavor(), Some(Flavor::WithA));
+    }
+
+    #[test]
+    fn flavor_preference() {
+        use FlavorPreference;
+        assert_eq!(
+            FlavorPreference::from(Flavor::Standard),
+            FlavorPreference::PreferStandard
+        );
+        assert!(FlavorPreference::PreferA.allows(Some(Flavor::Standard)));
+        assert!(FlavorPreference::OnlyA.allows(Some(Flavor::WithA)));
+        assert!(!FlavorPreference::OnlyA.allows(Some(Flavor::Standard)));
+        assert!(FlavorPreference::OnlyStandard.allows(None));
+        let mut search = SdkSearch::new();
+        let _ = search.flavor_preference(FlavorPreference::OnlyStandard);
+        assert_eq!(
+            search.locations(SdkVersion::V8_1),
+            vec![r"HKEY_LOCAL_MACHINE\SOFTWARE\Microsoft\Microsoft SDKs\Windows\v8.1"]
+        );
+        let _ = search.flavor_preference(FlavorPreference::PreferStandard);
+        assert_eq!(search.locations(SdkVersion::V8_1).len(), 2);
+    }
+
+    #[test]
+    fn find_with_report() {
+        let mut search = SdkSearch::new();
+        let _ = search.use_env(false).min_version(SdkVersion::V8_1);
+        let (res, report) = search.find_with_report();
+        let info = res.expect("could not retrieve Windows SDK info from registry");
+        assert_eq!(
+            info,
+            search
+                .find()
+                .expect("could not retrieve Windows SDK info from registry")
+        );
+        let steps = report.steps();
+        assert_eq!(steps[0].version(), SdkVersion::Env);
+        assert_eq!(*steps[0].outcome(), SearchOutcome::Skipped);
+        assert!(steps[0].locations().is_empty());
+        assert_eq!(steps[1].version(), SdkVersion::V10_0);
+        assert!(steps[1].locations()[0].starts_with("HKEY_LOCAL_MACHINE\\SOFTWARE"));
+        match (
+            info,
+            steps.last().map(super::super::report::SearchStep::outcome),
+        ) {
+            (Some(info), Some(SearchOutcome::Found { folder })) => {
+                assert_eq!(folder.as_path(), info.installation_folder());
+            }
+            (None, Some(outcome)) => assert_ne!(*outcome, SearchOutcome::NotFound),
+            other => panic!("report does not match search result: {:?}", other),
+        }
+    }
+
+    #[test]
+    #[cfg(target_os = "windows")]
+    fn require_existing_folder() {
+        let info = SdkSearch::new()
+            .require_existing_folder(true)
+            .find()
+            .expect("could not retrieve Windows SDK info from registry")
+            .expect("Windows SDK is not installed");
+        assert!(info.is_present());
+    }
+
+    #[test]
+    #[cfg(target_os = "windows")]
+    fn require_headers() {
+        let info = SdkSearch::new()
+            .require_headers(true)
+            .find()
+            .expect("could not retrieve Windows SDK info from registry")
+            .expect("Windows SDK is not installed");
+        assert!(info.has_headers());
+    }
+
+    #[test]
+    #[cfg(target_os = "windows")]
+    fn canonicalize_paths() {
+        use std::fs;
+        let info = SdkSearch::new()
+            .require_existing_folder(true)
+            .canonicalize_paths(true)
+            .find()
+            .expect("could not retrieve Windows SDK info from registry")
+            .expect("Windows SDK is not installed");
+        assert_eq!(
+            fs::canonicalize(info.installation_folder()).ok().as_ref(),
+            Some(&info.installation_folder)
+        );
+        let missing = SdkInfo {
+            installation_folder: PathBuf::from(r"C:\find-winsdk\does\not\exist"),
+            ..info
+        };
+        assert_eq!(
+            SdkSearch::new()
+                .canonicalize_paths(true)
+                .canonicalized(missing.clone()),
+            missing
+        );
+    }
+
+    #[test]
+    fn ignore_env() {
+        let info = SdkSearch::new()
+            .version(SdkVersion::Env)
+            .use_env(false)
+            .find()
+            .unwrap_or_else(|_| unreachable!());
+        assert_eq!(info, None::<SdkInfo>);
+    }
+
+    #[test]
+    #[cfg(target_os = "windows")]
+    fn registry_view_wow32() {
+        let _ = SdkSearch::new()
+            .version(SdkVersion::V10_0)
+            .registry_view(RegistryView::Wow32)
+            .find()
+            .expect("could not retrieve Windows 10 SDK info from registry")
+            .expect("Windows 10 SDK is not registered in the 32-bit registry view");
+    }
+
+    /// Returns a mock registry holding a Windows 10 SDK, both flavours of the Windows 8.1 SDK,
+    /// and a Windows 7.1 SDK recorded only in a component subkey.
+    fn mock_registry() -> MockRegistry {
+        MockRegistry::new()
+            .with_key(
+                V10_0_REG_KEY,
+                &[
+                    ("InstallationFolder", r"C:\Kits\10\"),
+                    ("ProductVersion", "10.0.19041"),
+                ],
+            )
+            .with_key(
+                V8_1A_REG_KEY,
+                &[
+                    ("InstallationFolder", r"C:\SDKs\v8.1A\"),
+                    ("ProductVersion", "8.1.51636"),
+                ],
+            )
+            .with_key(
+                V8_1_REG_KEY,
+                &[
+                    ("InstallationFolder", r"C:\Kits\8.1\"),
+                    ("ProductVersion", "8.1.25984"),
+                    (
+                        "ProductName",
+                        "Windows Software Development Kit for Windows 8.1",
+                    ),
+                ],
+            )
+            .with_key(
+                &format!(r"{V7_1_REG_KEY}\WinSDKTools"),
+                &[
+                    ("InstallationFolder", r"C:\SDKs\v7.1\bin\"),
+                    ("ProductVersion", "7.1.7600.0.30514"),
+                ],
+            )
+    }
+
+    /// Returns a search that only reads the registry, so that tests do not depend on the
+    /// environment.
+    fn registry_search() -> SdkSearch {
+        let mut search = SdkSearch::new();
+        let _ = search.use_env(false).use_prefer_var(false);
+        search
+    }
+
+    #[test]
+    fn mock_search_order() {
+        let _mock = mock_registry().install();
+        let search = registry_search();
+        let found = search
+            .find_detailed()
+            .expect("could not read mock registry")
+            .expect("mock SDK was not found");
+        assert_eq!(found.0, SdkVersion::V10_0);
+        assert_eq!(found.1.installation_folder(), PathBuf::from(r"C:\Kits\10"));
+        assert_eq!(found.1.product_version(), "10.0.19041");
+        let all = search.find_all().expect("could not read mock registry");
+        let versions: Vec<_> = all.iter().map(SdkInfo::product_version).collect();
+        assert_eq!(versions, ["10.0.19041", "8.1.51636", "7.1.7600.0.30514"]);
+        assert_eq!(all[1].flavor(), Some(Flavor::WithA));
+        // The v7.1 key has no values of its own, so its component subkey is used.
+        assert_eq!(
+            all[2].installation_folder(),
+            PathBuf::from(r"C:\SDKs\v7.1\bin")
+        );
+        let mut min = search.clone();
+        let _ = min.min_version(SdkVersion::V8_1).version(SdkVersion::V7_1);
+        assert_eq!(min.find().expect("could not read mock registry"), None);
+    }
+
+    #[test]
+    fn find_in_order() {
+        let _mock = mock_registry()
+            .with_error(V8_0_REG_KEY, io::ErrorKind::PermissionDenied)
+            .install();
+        let search = registry_search();
+        let found = search
+            .find_in_order(&[SdkVersion::V8_0, SdkVersion::V8_1, SdkVersion::V10_0])
+            .expect("tolerated error was not skipped")
+            .expect("mock SDK was not found");
+        assert_eq!(found.product_version(), "8.1.51636");
+        let found = search
+            .find_in_order(&[SdkVersion::V6_0, SdkVersion::V10_0])
+            .expect("could not read mock registry")
+            .expect("mock SDK was not found");
+        assert_eq!(found.product_version(), "10.0.19041");
+        assert_eq!(
+            search
+                .find_in_order(&[])
+                .expect("could not read mock registry"),
+            None
+        );
+        match search.find_in_order(&[SdkVersion::V8_0]) {
+            Err(FindSdkError::Registry(ref e)) => {
+                assert_eq!(e.kind(), io::ErrorKind::PermissionDenied);
+            }
+            other => panic!("expected access to be denied, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn exclude() {
+        let _mock = mock_registry()
+            .with_error(V7_1_REG_KEY, io::ErrorKind::Other)
+            .install();
+        let mut search = registry_search();
+        let _ = search.exclude(&[SdkVersion::V10_0, SdkVersion::V7_1]);
+        let all = search
+            .find_all()
+            .expect("excluded version was searched for");
+        let versions: Vec<_> = all.iter().map(SdkInfo::product_version).collect();
+        assert_eq!(versions, ["8.1.51636"]);
+        let (res, report) = search.find_with_report();
+        assert_eq!(
+            res.expect("excluded version was searched for"),
+            all.first().cloned()
+        );
+        assert!(report
+            .steps()
+            .iter()
+            .all(|step| step.version() != SdkVersion::V10_0));
+        let found = search
+            .version(SdkVersion::V10_0)
+            .find()
+            .expect("could not read mock registry");
+        assert_eq!(
+            found.map(|info| info.product_version().to_owned()),
+            Some("10.0.19041".to_owned())
+        );
+    }
+
+    #[test]
+    fn mock_flavor_preference() {
+        use FlavorPreference;
+        let _mock = mock_registry().install();
+        let find = |preference| {
+            registry_search()
+                .version(SdkVersion::V8_1)
+                .flavor_preference(preference)
+                .find()
+                .expect("could not read mock registry")
+                .expect("mock SDK was not found")
+        };
+        let standard = find(FlavorPreference::PreferStandard);
+        assert_eq!(standard.flavor(), Some(Flavor::Standard));
+        assert_eq!(
+            standard.product_name(),
+            Some("Windows Software Development Kit for Windows 8.1")
+        );
+        assert_eq!(find(FlavorPreference::OnlyA).flavor(), Some(Flavor::WithA));
+        let _mock = MockRegistry::new()
+            .with_key(
+                V8_1_REG_KEY,
+                &[
+                    ("InstallationFolder", r"C:\Kits\8.1\"),
+                    ("ProductVersion", "8.1.25984"),
+                ],
+            )
+            .install();
+        assert_eq!(
+            find(FlavorPreference::PreferA).flavor(),
+            Some(Flavor::Standard)
+        );
+        assert_eq!(
+            registry_search()
+                .version(SdkVersion::V8_1)
+                .flavor_preference(FlavorPreference::OnlyA)
+                .find()
+                .expect("could not read mock registry"),
+            None
+        );
+    }
+
+    #[test]
+    fn mock_installed_roots() {
+        let _mock = MockRegistry::new()
+            .with_key(
+                registry::INSTALLED_ROOTS_REG_KEY,
+                &[("KitsRoot10", r"C:\Kits\10\")],
+            )
+            .with_key(
+                &format!(r"{}\10.0.17763.0", registry::INSTALLED_ROOTS_REG_KEY),
+                &[],
+            )
+            .with_key(
+                &format!(r"{}\10.0.19041.0", registry::INSTALLED_ROOTS_REG_KEY),
+                &[("ProductName", "Windows 10 SDK")],
+            )
+            .install();
+        let info = registry_search()
+            .version(SdkVersion::V10_0)
+            .find()
+            .expect("could not read mock registry")
+            .expect("mock SDK was not found");
+        assert_eq!(info.installation_folder(), PathBuf::from(r"C:\Kits\10"));
+        assert_eq!(info.product_version(), "10.0.19041");
+        assert_eq!(info.product_name(), Some("Windows 10 SDK"));
+    }
+
+    #[test]
+    fn mock_installed_roots_preview() {
+        let _mock = MockRegistry::new()
+            .with_key(
+                registry::INSTALLED_ROOTS_REG_KEY,
+                &[("KitsRoot10", r"C:\Kits\10\")],
+            )
+            .with_key(
+                &format!(r"{}\10.0.22621.0", registry::INSTALLED_ROOTS_REG_KEY),
+                &[],
+            )
+            .with_key(
+                &format!(r"{}\10.0.25336.1000", registry::INSTALLED_ROOTS_REG_KEY),
+                &[],
+            )
+            .install();
+        let info = registry_search()
+            .find()
+            .expect("could not read mock registry")
+            .expect("mock SDK was not found");
+        assert_eq!(info.product_version(), "10.0.22621");
+        let preview = registry_search()
+            .include_preview(true)
+            .find()
+            .expect("could not read mock registry")
+            .expect("mock SDK was not found");
+        assert_eq!(preview.product_version(), "10.0.25336.1000");
+    }
+
+    #[test]
+    fn mock_errors() {
+        let _mock = mock_registry()
+            .with_key(V8_0_REG_KEY, &[("InstallationFolder", r"C:\Kits\8.0\")])
+            .with_error(V10_0_REG_KEY, io::ErrorKind::PermissionDenied)
+            .install();
+        let search = registry_search();
+        let all = search.find_all().expect("tolerated error was not skipped");
+        assert_eq!(all[0].product_version(), "8.1.51636");
+        assert_eq!(all.len(), 2);
+        match search.clone().version(SdkVersion::V10_0).find() {
+            Err(FindSdkError::Registry(ref e)) => {
+                assert_eq!(e.kind(), io::ErrorKind::PermissionDenied);
+            }
+            other => panic!("expected access to be denied, got {:?}", other),
+        }
+        match search.clone().version(SdkVersion::V8_0).find() {
+            Err(FindSdkError::MalformedValue { ref key }) => assert_eq!(key, V8_0_REG_KEY),
+            other => panic!("expected a malformed value error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn include_preview() {
+        use std::env;
+        use std::fs;
+        let root = env::temp_dir().join("find-winsdk-include-preview");
+        let res = fs::create_dir_all(root.join("Include").join("10.0.19041.0"))
+            .and_then(|()| fs::create_dir_all(root.join("Include").join("10.0.25336.1000")));
+        let folder = root.to_str().expect("invalid temporary folder");
+        let _mock = MockRegistry::new()
+            .with_key(
+                V10_0_REG_KEY,
+                &[
+                    ("InstallationFolder", folder),
+                    ("ProductVersion", "10.0.19041"),
+                ],
+            )
+            .install();
+        let mut search = registry_search();
+        let released = search.find_all_v10();
+        let all = search.include_preview(true).find_all_v10();
+        fs::remove_dir_all(&root).expect("could not delete test folder");
+        res.expect("could not create test folder");
+        let versions = |found: Result<Vec<SdkInfo>, FindSdkError>| -> Vec<String> {
+            found
+                .expect("could not read mock registry")
+                .iter()
+                .map(|info| info.product_version().to_owned())
+                .collect()
+        };
+        assert_eq!(versions(released), ["10.0.19041"]);
+        assert_eq!(versions(all), ["10.0.25336.1000", "10.0.19041"]);
+    }
+
+    #[test]
+    fn retries() {
+        let _mock = mock_registry()
+            .with_transient_error(V10_0_REG_KEY, io::ErrorKind::Other, 3)
+            .install();
+        let mut search = registry_search();
+        let _ = search.version(SdkVersion::V10_0).retries(1);
+        match search.find() {
+            Err(FindSdkError::Registry(ref e)) => assert_eq!(e.kind(), io::ErrorKind::Other),
+            other => panic!("expected the error to persist, got {:?}", other),
+        }
+        let info = search
+            .retries(1)
+            .find()
+            .expect("transient error was not retried")
+            .expect("mock SDK was not found");
+        assert_eq!(info.product_version(), "10.0.19041");
+        let _mock = mock_registry()
+            .with_error(V8_0_REG_KEY, io::ErrorKind::PermissionDenied)
+            .install();
+        match search.retries(3).version(SdkVersion::V8_0).find() {
+            Err(FindSdkError::Registry(ref e)) => {
+                assert_eq!(e.kind(), io::ErrorKind::PermissionDenied);
+            }
+            other => panic!("expected access to be denied, got {:?}", other),
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "vs-setup")]
+    fn accepts_v10_build() {
+        let build = |version: &str| SdkInfo {
+            installation_folder: PathBuf::from(r"C:\find-winsdk\does\not\exist"),
+            product_name: None,
+            product_version: version.to_owned(),
+            bin_dir_from_env: None,
+            flavor: None,
+            raw_values: BTreeMap::new(),
+            registry_last_write: None,
+        };
+        let released = build("10.0.19041");
+        let preview = build("10.0.25336.1000");
+        let mut search = SdkSearch::new();
+        assert!(search.accepts_v10_build(&released));
+        assert!(!search.accepts_v10_build(&preview));
+        assert!(search.include_preview(true).accepts_v10_build(&preview));
+        assert!(!search
+            .require_existing_folder(true)
+            .accepts_v10_build(&released));
+        assert!(!SdkSearch::new()
+            .require_headers(true)
+            .accepts_v10_build(&released));
+    }
+
+    #[test]
+    fn retries_os_error() {
+        // `ERROR_CANTREAD`, which has no more specific `io::ErrorKind` than `Uncategorized`.
+        const CANT_READ: i32 = 1012;
+        let _mock = mock_registry()
+            .with_transient_os_error(V10_0_REG_KEY, CANT_READ, 2)
+            .install();
+        let mut search = registry_search();
+        let _ = search.version(SdkVersion::V10_0).retries(1);
+        match search.find() {
+            Err(FindSdkError::Registry(ref e)) => assert_eq!(e.raw_os_error(), Some(CANT_READ)),
+            other => panic!("expected the error to persist, got {:?}", other),
+        }
+        let info = search
+            .find()
+            .expect("OS error was not retried")
+            .expect("mock SDK was not found");
+        assert_eq!(info.product_version(), "10.0.19041");
+    }
+
+    #[test]
+    fn mock_registry_view() {
+        let _mock = MockRegistry::new()
+            .with_key_in(
+                RegistryHive::CurrentUser,
+                RegistryView::Wow64,
+                V10_0_REG_KEY,
+                &[
+                    ("InstallationFolder", r"C:\Users\Kits\10"),
+                    ("ProductVersion", "10.0.19041"),
+                ],
+            )
+            .install();
+        let find = |hive, view| {
+            registry_search()
+                .version(SdkVersion::V10_0)
+                .registry_hive(hive)
+                .registry_view(view)
+                .find()
+                .expect("could not read mock registry")
+                .is_some()
+        };
+        assert!(find(RegistryHive::Any, RegistryView::Any));
+        assert!(find(RegistryHive::CurrentUser, RegistryView::Wow64));
+        assert!(!find(RegistryHive::LocalMachine, RegistryView::Any));
+        assert!(!find(RegistryHive::Any, RegistryView::Wow32));
+    }
+}
@@ -0,0 +1,126 @@
+// Copyright (c) 2018 FaultyRAM
+//
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or http://www.apache.org/licenses/LICENSE-2.0> or the
+// MIT license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at
+// your option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Numeric Windows SDK version numbers.
+
+use std::error::Error;
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
+
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+/// A numeric version number, such as `10.0.17763.0`.
+///
+/// Version numbers are ordered numerically, component by component.
+pub struct Version {
+    /// The major version number.
+    pub major: u32,
+    /// The minor version number.
+    pub minor: u32,
+    /// The build number.
+    pub build: u32,
+    /// The revision number.
+    pub revision: u32,
+}
+
+impl Version {
+    #[must_use]
+    /// Creates a new version number from its components.
+    pub fn new(major: u32, minor: u32, build: u32, revision: u32) -> Self {
+        Self {
+            major,
+            minor,
+            build,
+            revision,
+        }
+    }
+}
+
+impl Display for Version {
+    /// Formats a version number with all four components, e.g. `8.1.0.0`.
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}.{}.{}.{}",
+            self.major, self.minor, self.build, self.revision
+        )
+    }
+}
+
+impl FromStr for Version {
+    type Err = ParseVersionError;
+
+    /// Parses a version number from between one and four dot-separated numbers.
+    ///
+    /// Missing trailing components are taken to be zero, so `8.1` is equivalent to `8.1.0.0`. A
+    /// single `A` or `a` suffix on the last component, as used by releases such as v8.1A, is
+    /// ignored.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let err = ParseVersionError { _priv: () };
+        let s = if s.ends_with('A') || s.ends_with('a') {
+            &s[..s.len() - 1]
+        } else {
+            s
+        };
+        let mut components = [0; 4];
+        for (i, c) in s.split('.').enumerate() {
+            if i == components.len() {
+                return Err(err);
+            }
+            components[i] = c.parse().map_err(|_| err)?;
+        }
+        Ok(Self::new(
+            components[0],
+            components[1],
+            components[2],
+            components[3],
+        ))
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+/// The error returned when parsing a `Version` from a string fails.
+pub struct ParseVersionError {
+    _priv: (),
+}
+
+impl Display for ParseVersionError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.write_str("invalid Windows SDK version number")
+    }
+}
+
+impl Error for ParseVersionError {}
+
+#[cfg(test)]
+mod tests {
+    use Version;
+
+    #[test]
+    fn parse() {
+        assert_eq!("10.0.19041.0".parse(), Ok(Version::new(10, 0, 19041, 0)));
+        assert_eq!("10.0.17763".parse(), Ok(Version::new(10, 0, 17763, 0)));
+        assert_eq!("8.1".parse(), Ok(Version::new(8, 1, 0, 0)));
+        assert_eq!("6.0A".parse(), Ok(Version::new(6, 0, 0, 0)));
+        assert!("".parse::<Version>().is_err());
+        assert!("wdf".parse::<Version>().is_err());
+        assert!("10.0.x".parse::<Version>().is_err());
+        assert!("1.2.3.4.5".parse::<Version>().is_err());
+        assert!("6.0AA".parse::<Version>().is_err());
+    }
+
+    #[test]
+    fn ord() {
+        assert!(Version::new(10, 0, 19041, 0) > Version::new(10, 0, 9600, 0));
+        assert!(Version::new(10, 0, 0, 0) > Version::new(8, 1, 0, 0));
+    }
+
+    #[test]
+    fn display() {
+        assert_eq!(Version::new(8, 1, 0, 0).to_string(), "8.1.0.0");
+    }
+}
@@ -0,0 +1,162 @@
+// Copyright (c) 2018 FaultyRAM
+//
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or http://www.apache.org/licenses/LICENSE-2.0> or the
+// MIT license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at
+// your option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Detection of Windows 10 SDK builds installed by the Visual Studio Installer.
+
+use serde_json;
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+use {SdkInfo, Version};
+
+/// The package ID prefixes that the Visual Studio Installer uses for Windows SDK builds.
+const SDK_PACKAGE_PREFIXES: [&str; 2] = ["Win10SDK_", "Win11SDK_"];
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+/// The parts of a Visual Studio instance's `state.json` that describe its installed packages.
+struct InstanceState {
+    #[serde(default)]
+    selected_packages: Vec<Package>,
+}
+
+#[derive(Deserialize)]
+/// A package installed as part of a Visual Studio instance.
+struct Package {
+    id: String,
+}
+
+/// Returns installation information for each Windows 10 SDK build that a Visual Studio instance
+/// installed, newest first.
+///
+/// The Visual Studio Installer installs Windows 10 SDK builds into a Windows 10 SDK folder, so
+/// each build is looked for in `folders`, in order, and only builds that are present in one of
+/// them are returned. Instances whose `state.json` cannot be read are skipped.
+pub fn find_v10_builds(folders: &[PathBuf]) -> Vec<SdkInfo> {
+    v10_builds(&instance_states(), folders)
+}
+
+/// Returns installation information for each Windows 10 SDK build listed by the given contents of
+/// `state.json` files that is present in one of `folders`, newest first.
+fn v10_builds(states: &[String], folders: &[PathBuf]) -> Vec<SdkInfo> {
+    let mut builds: Vec<(Version, String)> = Vec::new();
+    for state in states {
+        for build in sdk_builds(state) {
+            if let Ok(version) = build.parse() {
+                if !builds.iter().any(|b| b.0 == version) {
+                    builds.push((version, build));
+                }
+            }
+        }
+    }
+    builds.sort_by_key(|&(version, _)| Reverse(version));
+    builds
+        .into_iter()
+        .filter_map(|(version, build)| {
+            let folder = folders
+                .iter()
+                .find(|folder| folder.join("Include").join(version.to_string()).is_dir())?;
+            Some(SdkInfo {
+                installation_folder: folder.clone(),
+                product_name: None,
+                product_version: build,
+                bin_dir_from_env: None,
+                flavor: None,
+                raw_values: BTreeMap::new(),
+                registry_last_write: None,
+            })
+        })
+        .collect()
+}
+
+/// Returns the contents of every Visual Studio instance's `state.json` that can be read.
+fn instance_states() -> Vec<String> {
+    let root = env::var_os("ProgramData")
+        .map_or_else(|| PathBuf::from(r"C:\ProgramData"), PathBuf::from)
+        .join(r"Microsoft\VisualStudio\Packages\_Instances");
+    let Ok(entries) = fs::read_dir(root) else {
+        return Vec::new();
+    };
+    entries
+        .filter_map(Result::ok)
+        .filter_map(|entry| fs::read_to_string(entry.path().join("state.json")).ok())
+        .collect()
+}
+
+/// Returns the Windows SDK build numbers, e.g. `10.0.19041`, installed according to the contents
+/// of a `state.json`.
+fn sdk_builds(state: &str) -> Vec<String> {
+    let state: InstanceState = match serde_json::from_str(state) {
+        Ok(state) => state,
+        Err(_) => return Vec::new(),
+    };
+    state
+        .selected_packages
+        .into_iter()
+        .filter_map(|package| {
+            SDK_PACKAGE_PREFIXES
+                .iter()
+                .find(|prefix| package.id.starts_with(*prefix))
+                .map(|prefix| package.id[prefix.len()..].to_owned())
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{sdk_builds, v10_builds};
+    use std::env;
+    use std::fs;
+
+    #[test]
+    fn state_json() {
+        let state = r#"{
+            "installationPath": "C:\\Program Files\\Microsoft Visual Studio\\2022\\Community",
+            "selectedPackages": [
+                { "id": "Microsoft.VisualStudio.Component.VC.Tools.x86.x64", "version": "17.0" },
+                { "id": "Win10SDK_10.0.19041", "version": "10.1.19041.685" },
+                { "id": "Win11SDK_10.0.22621", "version": "10.1.22621.755" }
+            ]
+        }"#;
+        assert_eq!(sdk_builds(state), vec!["10.0.19041", "10.0.22621"]);
+        assert!(sdk_builds("{}").is_empty());
+        assert!(sdk_builds("not json").is_empty());
+    }
+
+    #[test]
+    fn builds_in_folders() {
+        let root = env::temp_dir().join("find-winsdk-vs-setup-builds");
+        let (first, second) = (root.join("first"), root.join("second"));
+        let res = fs::create_dir_all(first.join("Include").join("10.0.19041.0"))
+            .and_then(|()| fs::create_dir_all(second.join("Include").join("10.0.19041.0")))
+            .and_then(|()| fs::create_dir_all(second.join("Include").join("10.0.22621.0")));
+        let state = r#"{
+            "selectedPackages": [
+                { "id": "Win10SDK_10.0.17763" },
+                { "id": "Win10SDK_10.0.19041" },
+                { "id": "Win11SDK_10.0.22621" }
+            ]
+        }"#;
+        let builds = v10_builds(&[state.to_owned()], &[first.clone(), second.clone()]);
+        fs::remove_dir_all(&root).expect("could not delete test folder");
+        res.expect("could not create test folder");
+        let found: Vec<_> = builds
+            .iter()
+            .map(|info| (info.product_version(), info.installation_folder()))
+            .collect();
+        assert_eq!(
+            found,
+            [
+                ("10.0.22621", second.as_path()),
+                ("10.0.19041", first.as_path())
+            ]
+        );
+    }
+}
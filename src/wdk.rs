@@ -0,0 +1,136 @@
+// Copyright (c) 2018 FaultyRAM
+//
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or http://www.apache.org/licenses/LICENSE-2.0> or the
+// MIT license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at
+// your option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Detection of Windows Driver Kit installations.
+
+use registry::{self, RegistryHive, RegistryView};
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use {FindSdkError, Version};
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+/// Information about a Windows Driver Kit (WDK) installation.
+///
+/// The WDK for Windows 10 is installed into the same Windows Kits folder as the Windows 10 SDK,
+/// adding kernel-mode (`km`) headers and libraries to each build it supports.
+pub struct WdkInfo {
+    installation_folder: PathBuf,
+    product_version: String,
+}
+
+impl WdkInfo {
+    /// Returns installation information for the newest installed Windows Driver Kit build.
+    ///
+    /// This reads the Windows Kits root folder from the registry, then looks for the newest build
+    /// with a `km` folder under `Include`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `FindSdkError::Registry` if the registry, or the `Include` folder, cannot be read. A
+    /// missing `Include` folder is not an error.
+    pub fn find() -> Result<Option<Self>, FindSdkError> {
+        let Some(root) = registry::kits_root10(RegistryHive::LocalMachine, RegistryView::Any)?
+        else {
+            return Ok(None);
+        };
+        let entries = match fs::read_dir(root.join("Include")) {
+            Ok(entries) => entries,
+            Err(ref e) if e.kind() == ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(FindSdkError::Registry(e)),
+        };
+        let newest = entries
+            .filter_map(Result::ok)
+            .filter(|entry| entry.path().join("km").is_dir())
+            .filter_map(|entry| entry.file_name().into_string().ok())
+            .filter_map(|name| name.parse::<Version>().ok().map(|v| (v, name)))
+            .max();
+        Ok(newest.map(|(_, product_version)| Self {
+            installation_folder: root,
+            product_version,
+        }))
+    }
+
+    #[must_use]
+    /// Returns the filesystem path to where a Windows Driver Kit instance is installed.
+    pub fn installation_folder(&self) -> &Path {
+        &self.installation_folder
+    }
+
+    #[must_use]
+    /// Returns the build number of a Windows Driver Kit instance, e.g. `10.0.17763.0`.
+    pub fn product_version(&self) -> &str {
+        &self.product_version
+    }
+
+    #[must_use]
+    /// Returns the path to a Windows Driver Kit instance's kernel-mode header files.
+    pub fn include_dir(&self) -> PathBuf {
+        self.installation_folder
+            .join("Include")
+            .join(&self.product_version)
+            .join("km")
+    }
+
+    #[must_use]
+    /// Returns the path to the root of a Windows Driver Kit instance's kernel-mode library files.
+    ///
+    /// Libraries for each architecture are in subfolders of this folder, e.g. `x64`.
+    pub fn lib_dir(&self) -> PathBuf {
+        self.installation_folder
+            .join("Lib")
+            .join(&self.product_version)
+            .join("km")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::WdkInfo;
+    use registry::{self, MockRegistry};
+    use std::env;
+    use std::fs;
+    use FindSdkError;
+
+    fn mock_kits_root(root: &str) -> MockRegistry {
+        MockRegistry::new().with_key(registry::INSTALLED_ROOTS_REG_KEY, &[("KitsRoot10", root)])
+    }
+
+    #[test]
+    #[cfg(target_os = "windows")]
+    fn wdk() {
+        let info = WdkInfo::find()
+            .expect("could not retrieve WDK info from registry")
+            .expect("WDK is not installed");
+        assert!(info.include_dir().is_dir());
+        assert!(info.lib_dir().is_dir());
+    }
+
+    #[test]
+    fn find_without_include() {
+        let root = env::temp_dir().join("find-winsdk-wdk-without-include");
+        let _mock = mock_kits_root(root.to_str().expect("invalid temporary folder")).install();
+        assert_eq!(WdkInfo::find().expect("could not search for WDK"), None);
+    }
+
+    #[test]
+    fn find_unreadable_include() {
+        let root = env::temp_dir().join("find-winsdk-wdk-unreadable-include");
+        let res = fs::create_dir_all(&root).and_then(|()| fs::write(root.join("Include"), ""));
+        let found = {
+            let _mock = mock_kits_root(root.to_str().expect("invalid temporary folder")).install();
+            WdkInfo::find()
+        };
+        fs::remove_dir_all(&root).expect("could not delete test folder");
+        res.expect("could not create test folder");
+        match found {
+            Err(FindSdkError::Registry(_)) => (),
+            res => panic!("expected an IO error, got {:?}", res),
+        }
+    }
+}